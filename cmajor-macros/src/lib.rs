@@ -0,0 +1,234 @@
+#![warn(missing_docs)]
+
+//! Procedural macros for the `cmajor` crate.
+
+use {
+    proc_macro::TokenStream,
+    proc_macro2::TokenStream as TokenStream2,
+    quote::quote,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+    syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Type},
+};
+
+/// Derive conversions between a struct and a Cmajor object value.
+///
+/// Generates `From<T> for ObjectValue`, `From<T> for Value` and `TryFrom<ValueRef<'_>> for T`,
+/// using the struct's field names as the object's field names (in declaration order).
+///
+/// The object's class name defaults to the name of the struct, and can be overridden with
+/// `#[cmajor(class = "...")]`.
+#[proc_macro_derive(CmajorValue, attributes(cmajor))]
+pub fn derive_cmajor_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let class = class_name(&input)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "`CmajorValue` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`CmajorValue` can only be derived for structs",
+            ))
+        }
+    };
+
+    let idents: Vec<_> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().expect("named fields have identifiers"))
+        .collect();
+    let names: Vec<_> = idents.iter().map(|ident| ident.to_string()).collect();
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+
+    for ty in &types {
+        check_field_type(ty)?;
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for ::cmajor::value::ObjectValue
+        #where_clause
+        {
+            fn from(value: #name #ty_generics) -> Self {
+                let #name { #(#idents),* } = value;
+                ::cmajor::value::ObjectValue::builder(#class)
+                    #(.field(#names, #idents))*
+                    .build()
+                    .expect("derived object should have valid fields")
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for ::cmajor::value::Value
+        #where_clause
+        {
+            fn from(value: #name #ty_generics) -> Self {
+                ::cmajor::value::ObjectValue::from(value).into()
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<::cmajor::value::ValueRef<'_>> for #name #ty_generics
+        #where_clause
+        {
+            type Error = ();
+
+            fn try_from(value: ::cmajor::value::ValueRef<'_>) -> ::core::result::Result<Self, Self::Error> {
+                let object = match value {
+                    ::cmajor::value::ValueRef::Object(object) => object,
+                    _ => return Err(()),
+                };
+
+                Ok(Self {
+                    #(
+                        #idents: <#types as ::core::convert::TryFrom<::cmajor::value::ValueRef<'_>>>::try_from(
+                            object.field(#names).ok_or(())?,
+                        )
+                        .map_err(|_| ())?,
+                    )*
+                })
+            }
+        }
+    })
+}
+
+/// Include a Cmajor program from a file as a `&'static str`.
+///
+/// Like `include_str!`, the path is resolved relative to the file the macro is invoked from,
+/// and a file that can't be read is a compile error. The program itself is checked when it is
+/// parsed, as the Cmajor library is only loaded at runtime.
+///
+/// ```ignore
+/// const PROGRAM: &str = cmajor::include_cmajor!("synth.cmajor");
+/// ```
+#[proc_macro]
+pub fn include_cmajor(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    let source_dir = proc_macro::Span::call_site()
+        .local_file()
+        .and_then(|file| file.parent().map(Path::to_owned))
+        .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from))
+        .unwrap_or_default();
+
+    expand_include(&path, &source_dir)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_include(path: &LitStr, source_dir: &Path) -> syn::Result<TokenStream2> {
+    let resolved = source_dir.join(path.value());
+
+    let source = fs::read_to_string(&resolved).map_err(|error| {
+        syn::Error::new_spanned(
+            path,
+            format!("couldn't read `{}`: {error}", resolved.display()),
+        )
+    })?;
+
+    if source.trim().is_empty() {
+        return Err(syn::Error::new_spanned(
+            path,
+            format!("`{}` is empty", resolved.display()),
+        ));
+    }
+
+    // Expand to `include_str!` so the file is embedded and tracked for changes by the compiler.
+    Ok(quote! { ::core::include_str!(#path) })
+}
+
+/// Reject primitive types that have no Cmajor equivalent, naming the types that are supported.
+fn check_field_type(ty: &Type) -> syn::Result<()> {
+    const UNSUPPORTED: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i128", "isize", "char",
+    ];
+
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            if let Some(ident) = path.path.get_ident() {
+                if UNSUPPORTED.iter().any(|unsupported| ident == unsupported) {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        format!(
+                            "`{ident}` has no Cmajor equivalent; the supported primitive types \
+                             are `bool`, `i32`, `i64`, `f32` and `f64`"
+                        ),
+                    ));
+                }
+            }
+            Ok(())
+        }
+        Type::Array(array) => check_field_type(&array.elem),
+        _ => Ok(()),
+    }
+}
+
+fn class_name(input: &DeriveInput) -> syn::Result<String> {
+    let mut class = input.ident.to_string();
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cmajor"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("class") {
+                class = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `cmajor` attribute"))
+            }
+        })?;
+    }
+
+    Ok(class)
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, syn::parse_quote};
+
+    #[test]
+    fn unsupported_field_types_are_named_in_the_error() {
+        let input: DeriveInput = parse_quote! {
+            struct Params {
+                gain: f32,
+                channels: [u8; 2],
+            }
+        };
+
+        let error = expand(input).unwrap_err().to_string();
+        assert!(error.contains("`u8` has no Cmajor equivalent"));
+        assert!(error.contains("`bool`, `i32`, `i64`, `f32` and `f64`"));
+    }
+
+    #[test]
+    fn included_programs_must_exist() {
+        let dir = std::env::temp_dir();
+        let name = format!("cmajor-include-{}.cmajor", std::process::id());
+        fs::write(dir.join(&name), "processor P {}").unwrap();
+
+        let result = expand_include(&LitStr::new(&name, proc_macro2::Span::call_site()), &dir);
+        fs::remove_file(dir.join(&name)).unwrap();
+        assert!(result.is_ok());
+
+        let error = expand_include(&parse_quote!("cmajor-missing.cmajor"), &dir)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("couldn't read"));
+    }
+}
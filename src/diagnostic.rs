@@ -3,6 +3,7 @@
 use {
     serde::Deserialize,
     serde_json::{Map as JsonMap, Value as JsonValue},
+    std::fmt::{self, Display, Formatter},
 };
 
 /// A diagnostic message from the compiler or engine.
@@ -28,6 +29,102 @@ pub struct DiagnosticMessage {
     _rest: JsonMap<String, JsonValue>,
 }
 
+/// A list of diagnostic messages from the compiler or engine.
+#[derive(Debug, Default, Deserialize)]
+#[serde(from = "DiagnosticMessages")]
+pub struct Diagnostics {
+    messages: Vec<DiagnosticMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DiagnosticMessages {
+    Single(Box<DiagnosticMessage>),
+    List(Vec<DiagnosticMessage>),
+}
+
+impl From<DiagnosticMessages> for Diagnostics {
+    fn from(messages: DiagnosticMessages) -> Self {
+        let messages = match messages {
+            DiagnosticMessages::Single(message) => vec![*message],
+            DiagnosticMessages::List(messages) => messages,
+        };
+        Self { messages }
+    }
+}
+
+impl Diagnostics {
+    pub(crate) fn append(&mut self, other: Diagnostics) {
+        self.messages.extend(other.messages);
+    }
+
+    /// Returns an iterator over the diagnostic messages.
+    pub fn iter(&self) -> impl Iterator<Item = &DiagnosticMessage> {
+        self.messages.iter()
+    }
+
+    /// The number of diagnostic messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Whether there are no diagnostic messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns an iterator over the messages with the given severity.
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &DiagnosticMessage> {
+        self.iter()
+            .filter(move |message| message.severity == severity)
+    }
+
+    /// Returns an iterator over the error messages.
+    pub fn errors(&self) -> impl Iterator<Item = &DiagnosticMessage> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Returns an iterator over the warning messages.
+    pub fn warnings(&self) -> impl Iterator<Item = &DiagnosticMessage> {
+        self.with_severity(Severity::Warning)
+    }
+
+    /// Whether any of the messages are errors.
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, message) in self.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = DiagnosticMessage;
+    type IntoIter = std::vec::IntoIter<DiagnosticMessage>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a DiagnosticMessage;
+    type IntoIter = std::slice::Iter<'a, DiagnosticMessage>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.iter()
+    }
+}
+
 /// A diagnostic category.
 #[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
 pub enum Category {
@@ -109,4 +206,151 @@ impl DiagnosticMessage {
     pub fn full_description(&self) -> &str {
         &self.full_description
     }
+
+    /// Format the message in the style of a compiler error, optionally with ANSI colours.
+    ///
+    /// The first line has the form `file:line:column: severity: message`, followed by the source
+    /// line with a caret under the column (when available).
+    pub fn to_pretty_string(&self, use_color: bool) -> String {
+        const BOLD: &str = "\x1b[1m";
+        const RESET: &str = "\x1b[0m";
+
+        let (bold, color, reset) = if use_color {
+            (BOLD, self.severity.color(), RESET)
+        } else {
+            ("", "", "")
+        };
+
+        let mut output = String::from(bold);
+        if let Some(file_name) = self.file_name() {
+            output.push_str(file_name);
+            output.push(':');
+        }
+        output.push_str(&format!(
+            "{}:{}:{reset} {color}{}:{reset} {bold}{}{reset}",
+            self.line_number, self.column_number, self.severity, self.message
+        ));
+
+        let annotated_line = if self.annotated_line.is_empty() && !self.source_line.is_empty() {
+            let caret = " ".repeat(self.column_number.saturating_sub(1));
+            format!("{}\n{caret}^", self.source_line)
+        } else {
+            self.annotated_line.clone()
+        };
+
+        if let Some((source_line, caret)) = annotated_line.split_once('\n') {
+            output.push_str(&format!("\n{source_line}\n{color}{caret}{reset}"));
+        } else if !annotated_line.is_empty() {
+            output.push_str(&format!("\n{annotated_line}"));
+        }
+
+        output
+    }
+}
+
+impl Display for DiagnosticMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_pretty_string(false))
+    }
+}
+
+impl Severity {
+    fn color(self) -> &'static str {
+        match self {
+            Self::Error => "\x1b[1;31m",
+            Self::Warning => "\x1b[1;33m",
+            Self::Note => "\x1b[1;36m",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MESSAGE: &str = r#"
+        {
+            "category": "compile",
+            "severity": "error",
+            "message": "Expected a stream type specifier",
+            "fileName": "",
+            "sourceLine": "input stweam int in;",
+            "columnNumber": 7,
+            "lineNumber": 3,
+            "annotatedLine": "input stweam int in;\n      ^",
+            "fullDescription": "3:7: error: Expected a stream type specifier"
+        }
+    "#;
+
+    #[test]
+    fn parse_a_single_diagnostic_message() {
+        let diagnostics: Diagnostics = serde_json::from_str(MESSAGE).unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+
+        let message = diagnostics.iter().next().unwrap();
+        assert_eq!(message.severity(), Severity::Error);
+        assert_eq!(message.location(), Location { line: 3, column: 7 });
+    }
+
+    #[test]
+    fn format_a_diagnostic_message() {
+        let diagnostics: Diagnostics = serde_json::from_str(MESSAGE).unwrap();
+        let message = diagnostics.iter().next().unwrap();
+
+        assert_eq!(
+            message.to_string(),
+            "3:7: error: Expected a stream type specifier\ninput stweam int in;\n      ^"
+        );
+
+        let colored = message.to_pretty_string(true);
+        assert!(colored.contains("\x1b[1;31merror:\x1b[0m"));
+        assert!(colored.ends_with("\x1b[1;31m      ^\x1b[0m"));
+    }
+
+    #[test]
+    fn parse_a_list_of_diagnostic_messages() {
+        let json = format!("[{MESSAGE}, {MESSAGE}]");
+        let diagnostics: Diagnostics = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|message| message.message() == "Expected a stream type specifier"));
+    }
+
+    #[test]
+    fn diagnostics_can_be_appended() {
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.append(serde_json::from_str(MESSAGE).unwrap());
+        diagnostics.append(serde_json::from_str(&format!("[{MESSAGE}, {MESSAGE}]")).unwrap());
+
+        assert_eq!(diagnostics.len(), 3);
+    }
+
+    #[test]
+    fn filter_diagnostics_by_severity() {
+        let warning = MESSAGE.replace(r#""severity": "error""#, r#""severity": "warning""#);
+        let json = format!("[{MESSAGE}, {warning}]");
+        let diagnostics: Diagnostics = serde_json::from_str(&json).unwrap();
+
+        assert!(diagnostics.has_errors());
+        assert_eq!(diagnostics.errors().count(), 1);
+        assert_eq!(diagnostics.warnings().count(), 1);
+        assert_eq!(diagnostics.with_severity(Severity::Note).count(), 0);
+        assert_eq!(diagnostics.to_string().lines().count(), 6);
+
+        let warnings: Diagnostics = serde_json::from_str(&warning).unwrap();
+        assert!(!warnings.has_errors());
+    }
 }
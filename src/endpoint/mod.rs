@@ -3,10 +3,18 @@
 use {
     crate::{
         engine::Annotation,
-        value::types::{Type, TypeRef},
+        value::{
+            types::{Type, TypeRef},
+            Value,
+        },
     },
     serde::{Deserialize, Serialize},
-    std::borrow::Borrow,
+    serde_json::{Map as JsonMap, Value as JsonValue},
+    std::{
+        borrow::Borrow,
+        fmt::{self, Display, Formatter},
+        str::FromStr,
+    },
 };
 
 /// An endpoint identifier.
@@ -70,6 +78,55 @@ pub enum EndpointDirection {
     Output,
 }
 
+impl Display for EndpointDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Input => "input",
+            Self::Output => "output",
+        })
+    }
+}
+
+/// An error returned when parsing an [`EndpointDirection`] from a string fails.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid endpoint direction: {0:?}")]
+pub struct ParseEndpointDirectionError(String);
+
+/// Parses `"input"` or `"output"`, as written by the [`Display`] implementation.
+///
+/// # Example
+///
+/// ```
+/// # use cmajor::endpoint::EndpointDirection;
+/// let direction: EndpointDirection = "output".parse().unwrap();
+/// assert_eq!(direction, EndpointDirection::Output);
+/// assert_eq!(direction.to_string(), "output");
+/// ```
+impl FromStr for EndpointDirection {
+    type Err = ParseEndpointDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "input" => Ok(Self::Input),
+            "output" => Ok(Self::Output),
+            _ => Err(ParseEndpointDirectionError(s.to_owned())),
+        }
+    }
+}
+
+/// The kind of an endpoint.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EndpointKind {
+    /// A stream endpoint.
+    Stream,
+
+    /// An event endpoint.
+    Event,
+
+    /// A value endpoint.
+    Value,
+}
+
 /// A stream endpoint.
 #[derive(Debug, Clone)]
 pub struct StreamEndpoint {
@@ -77,6 +134,7 @@ pub struct StreamEndpoint {
     direction: EndpointDirection,
     ty: Type,
     annotation: Annotation,
+    extra: JsonMap<String, JsonValue>,
 }
 
 impl From<StreamEndpoint> for EndpointInfo {
@@ -92,6 +150,7 @@ pub struct EventEndpoint {
     direction: EndpointDirection,
     ty: Vec<Type>,
     annotation: Annotation,
+    extra: JsonMap<String, JsonValue>,
 }
 
 impl From<EventEndpoint> for EndpointInfo {
@@ -107,6 +166,7 @@ pub struct ValueEndpoint {
     direction: EndpointDirection,
     ty: Type,
     annotation: Annotation,
+    extra: JsonMap<String, JsonValue>,
 }
 
 impl From<ValueEndpoint> for EndpointInfo {
@@ -125,6 +185,15 @@ impl EndpointInfo {
         }
     }
 
+    /// The kind of the endpoint.
+    pub fn kind(&self) -> EndpointKind {
+        match self {
+            Self::Stream(_) => EndpointKind::Stream,
+            Self::Event(_) => EndpointKind::Event,
+            Self::Value(_) => EndpointKind::Value,
+        }
+    }
+
     /// The endpoint's direction.
     pub fn direction(&self) -> EndpointDirection {
         match self {
@@ -134,6 +203,16 @@ impl EndpointInfo {
         }
     }
 
+    /// Whether the endpoint is an input.
+    pub fn is_input(&self) -> bool {
+        self.direction() == EndpointDirection::Input
+    }
+
+    /// Whether the endpoint is an output.
+    pub fn is_output(&self) -> bool {
+        self.direction() == EndpointDirection::Output
+    }
+
     /// The endpoint's annotation.
     pub fn annotation(&self) -> &Annotation {
         match self {
@@ -143,6 +222,45 @@ impl EndpointInfo {
         }
     }
 
+    /// Get a property of the endpoint that isn't otherwise exposed, such as its `purpose`.
+    ///
+    /// These are the properties of the endpoint's details that the crate doesn't model, as
+    /// reported by the library.
+    pub fn extra(&self, key: impl AsRef<str>) -> Option<&JsonValue> {
+        self.extras().get(key.as_ref())
+    }
+
+    fn extras(&self) -> &JsonMap<String, JsonValue> {
+        match self {
+            Self::Stream(endpoint) => &endpoint.extra,
+            Self::Event(endpoint) => &endpoint.extra,
+            Self::Value(endpoint) => &endpoint.extra,
+        }
+    }
+
+    pub(crate) fn with_extras(mut self, extras: JsonMap<String, JsonValue>) -> Self {
+        match &mut self {
+            Self::Stream(endpoint) => endpoint.extra = extras,
+            Self::Event(endpoint) => endpoint.extra = extras,
+            Self::Value(endpoint) => endpoint.extra = extras,
+        }
+        self
+    }
+
+    /// The endpoint's initial value, taken from the `init` property of its annotation.
+    ///
+    /// If there is no `init` property, the `min` property is used instead. The property is
+    /// coerced to the endpoint's type (or the first of its types that it fits). Returns `None` if
+    /// neither property exists, or if it can't be converted.
+    pub fn initial_value(&self) -> Option<Value> {
+        let annotation = self.annotation();
+        let json = annotation.get("init").or_else(|| annotation.get("min"))?;
+
+        self.types()
+            .iter()
+            .find_map(|ty| Value::from_json(ty, json).ok())
+    }
+
     /// Get the endpoints type or types.
     pub fn types(&self) -> &[Type] {
         match self {
@@ -189,6 +307,7 @@ impl ValueEndpoint {
             direction,
             ty,
             annotation,
+            extra: JsonMap::new(),
         }
     }
 
@@ -225,6 +344,7 @@ impl StreamEndpoint {
             direction,
             ty,
             annotation,
+            extra: JsonMap::new(),
         }
     }
 
@@ -262,6 +382,7 @@ impl EventEndpoint {
             direction,
             ty,
             annotation,
+            extra: JsonMap::new(),
         }
     }
 
@@ -286,10 +407,14 @@ impl EventEndpoint {
     }
 
     /// The index of the given type in the endpoint's type list.
+    ///
+    /// An exact match is preferred, otherwise the first type with the same shape is used, so that
+    /// objects whose class names differ from the program's can still be posted.
     pub fn type_index(&self, ty: TypeRef<'_>) -> Option<EndpointTypeIndex> {
         self.ty
             .iter()
             .position(|t| t.as_ref() == ty)
+            .or_else(|| self.ty.iter().position(|t| t.as_ref().structurally_eq(ty)))
             .map(EndpointTypeIndex::from)
     }
 
@@ -314,3 +439,32 @@ impl From<EndpointTypeIndex> for usize {
         index.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn endpoint_directions_round_trip_through_strings() {
+        for direction in [EndpointDirection::Input, EndpointDirection::Output] {
+            assert_eq!(
+                direction.to_string().parse::<EndpointDirection>().ok(),
+                Some(direction)
+            );
+        }
+
+        assert!("Input".parse::<EndpointDirection>().is_err());
+    }
+
+    #[test]
+    fn endpoint_type_indices_round_trip_through_serde() {
+        let index = EndpointTypeIndex::from(3);
+
+        let json = serde_json::to_string(&index).unwrap();
+        assert_eq!(json, "3");
+        assert_eq!(
+            serde_json::from_str::<EndpointTypeIndex>(&json).unwrap(),
+            index
+        );
+    }
+}
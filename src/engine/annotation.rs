@@ -2,3 +2,41 @@ use crate::json::{Map, Value};
 
 /// An annotation attached to a definition.
 pub type Annotation = Map<String, Value>;
+
+/// Typed helpers for common [`Annotation`] lookups.
+pub trait AnnotationExt {
+    /// Get a value as an `f64`, if it exists and is a number.
+    fn get_f64(&self, key: &str) -> Option<f64>;
+
+    /// Get a value as an `i64`, if it exists and is an integer.
+    fn get_i64(&self, key: &str) -> Option<i64>;
+
+    /// Get a value as a string, if it exists and is a string.
+    fn get_str(&self, key: &str) -> Option<&str>;
+
+    /// Get a value as a `bool`, if it exists and is a boolean.
+    fn get_bool(&self, key: &str) -> Option<bool>;
+
+    /// Get the `min` and `max` values, if both exist and are numbers.
+    fn get_range(&self) -> Option<(f64, f64)> {
+        Some((self.get_f64("min")?, self.get_f64("max")?))
+    }
+}
+
+impl AnnotationExt for Annotation {
+    fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(Value::as_f64)
+    }
+
+    fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(Value::as_i64)
+    }
+
+    fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key).and_then(Value::as_bool)
+    }
+}
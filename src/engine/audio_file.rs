@@ -0,0 +1,222 @@
+use {
+    crate::value::{
+        types::{Array, Object, Type},
+        ArrayValue, ObjectValue, Value,
+    },
+    hound::{SampleFormat, WavReader},
+    std::path::Path,
+};
+
+/// An error that can occur when loading an audio file.
+#[derive(thiserror::Error, Debug)]
+pub enum AudioFileError {
+    /// Failed to read the audio file.
+    #[error("Failed to read audio file: {0}")]
+    FailedToRead(#[from] hound::Error),
+
+    /// The audio file has more channels than are supported.
+    #[error("Unsupported number of channels: {0}")]
+    UnsupportedChannelCount(u16),
+}
+
+/// The maximum number of channels an audio file can have.
+const MAX_CHANNELS: u16 = 8;
+
+/// The contents of an audio file, as interleaved samples.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AudioFile {
+    samples: Vec<f32>,
+    num_channels: usize,
+    sample_rate: f64,
+}
+
+/// Group interleaved samples into an array of `float32[N]` frames.
+macro_rules! frames {
+    ($samples:expr, $n:literal) => {
+        $samples
+            .chunks_exact($n)
+            .map(|frame| <[f32; $n]>::try_from(frame).expect("chunks are exactly N samples"))
+            .collect::<ArrayValue>()
+    };
+}
+
+impl AudioFile {
+    pub(crate) fn read(path: impl AsRef<Path>) -> Result<Self, AudioFileError> {
+        let reader = WavReader::open(path)?;
+        let spec = reader.spec();
+
+        if spec.channels == 0 || spec.channels > MAX_CHANNELS {
+            return Err(AudioFileError::UnsupportedChannelCount(spec.channels));
+        }
+
+        let samples = match spec.sample_format {
+            SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+            SampleFormat::Int => {
+                let scale = 1.0 / (1_i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|sample| sample.map(|sample| sample as f32 * scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+
+        Ok(Self {
+            samples,
+            num_channels: usize::from(spec.channels),
+            sample_rate: f64::from(spec.sample_rate),
+        })
+    }
+
+    fn num_frames(&self) -> usize {
+        self.samples.len() / self.num_channels
+    }
+
+    /// Convert the audio into a value of the type the program declared for the external.
+    ///
+    /// Supported types are arrays of `float32` (mono), arrays of `float32[N]` or `float32<N>`
+    /// frames (`N` channels), and objects with a `frames` field of one of those types and an
+    /// optional `sampleRate` field. If the type isn't known, the audio is loaded as mono samples
+    /// or an array of frames, depending on the number of channels.
+    pub(crate) fn to_value(&self, ty: Option<&Type>) -> Result<Value, String> {
+        match ty {
+            None => Ok(self.frames().into()),
+            Some(Type::Array(array)) => self.frames_as(array).map(Value::from),
+            Some(Type::Object(object)) => self.object_as(object).map(Value::from),
+            Some(ty) => Err(format!(
+                "can't load an audio file into a value of type {ty:?}"
+            )),
+        }
+    }
+
+    fn frames_as(&self, array: &Array) -> Result<ArrayValue, String> {
+        let num_channels = match array.elem_ty() {
+            Type::Float32 => 1,
+            Type::Array(frame) if frame.elem_ty() == &Type::Float32 => frame.len(),
+            ty => {
+                return Err(format!(
+                    "can't load audio frames into elements of type {ty:?}"
+                ))
+            }
+        };
+
+        if num_channels != self.num_channels {
+            return Err(format!(
+                "expected audio with {num_channels} channel(s), but the file has {}",
+                self.num_channels
+            ));
+        }
+
+        if !array.is_empty() && array.len() != self.num_frames() {
+            return Err(format!(
+                "expected audio with {} frames, but the file has {}",
+                array.len(),
+                self.num_frames()
+            ));
+        }
+
+        Ok(self.frames())
+    }
+
+    fn object_as(&self, object: &Object) -> Result<ObjectValue, String> {
+        let mut builder = ObjectValue::builder(object.class());
+        for field in object.fields() {
+            builder = match (field.name(), field.ty()) {
+                ("frames", Type::Array(array)) => builder.field("frames", self.frames_as(array)?),
+                ("sampleRate", Type::Float64) => builder.field("sampleRate", self.sample_rate),
+                ("sampleRate", Type::Float32) => {
+                    builder.field("sampleRate", self.sample_rate as f32)
+                }
+                (name, ty) => {
+                    return Err(format!(
+                        "can't load audio into field `{name}` of type {ty:?}"
+                    ))
+                }
+            };
+        }
+
+        builder.build().map_err(|error| error.to_string())
+    }
+
+    fn frames(&self) -> ArrayValue {
+        match self.num_channels {
+            1 => ArrayValue::from_slice(&self.samples),
+            2 => frames!(&self.samples, 2),
+            3 => frames!(&self.samples, 3),
+            4 => frames!(&self.samples, 4),
+            5 => frames!(&self.samples, 5),
+            6 => frames!(&self.samples, 6),
+            7 => frames!(&self.samples, 7),
+            8 => frames!(&self.samples, 8),
+            _ => unreachable!("channel count is checked when the file is read"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::value::ValueRef,
+        hound::{WavSpec, WavWriter},
+    };
+
+    fn write_wav(name: &str, channels: u16, samples: &[f32]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}.wav", std::process::id()));
+        let spec = WavSpec {
+            channels,
+            sample_rate: 44_100,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        path
+    }
+
+    #[test]
+    fn stereo_files_are_loaded_as_frames() {
+        let path = write_wav("cmajor-stereo", 2, &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+        let audio = AudioFile::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let frame = Type::from(Array::new(Type::Float32, 2));
+        let value = audio
+            .to_value(Some(&Array::new(frame.clone(), 0).into()))
+            .unwrap();
+
+        let ValueRef::Array(frames) = value.as_ref() else {
+            panic!("expected an array");
+        };
+        assert_eq!(frames.len(), 3);
+        assert_eq!(<[f32; 2]>::try_from(frames.get(1).unwrap()), Ok([0.3, 0.4]));
+
+        let object = Object::new("Audio")
+            .with_field("frames", Array::new(frame, 3))
+            .with_field("sampleRate", Type::Float64);
+        let value = audio.to_value(Some(&object.into())).unwrap();
+        let ValueRef::Object(object) = value.as_ref() else {
+            panic!("expected an object");
+        };
+        assert_eq!(
+            object.field("sampleRate"),
+            Some(ValueRef::Float64(44_100.0))
+        );
+    }
+
+    #[test]
+    fn mismatched_shapes_are_rejected() {
+        let path = write_wav("cmajor-mismatch", 2, &[0.0; 8]);
+        let audio = AudioFile::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mono = Type::from(Array::new(Type::Float32, 0));
+        assert!(audio.to_value(Some(&mono)).is_err());
+
+        let too_long = Type::from(Array::new(Array::new(Type::Float32, 2), 5));
+        assert!(audio.to_value(Some(&too_long)).is_err());
+    }
+}
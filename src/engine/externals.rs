@@ -1,16 +1,62 @@
-use {crate::value::Value, std::collections::HashMap};
+#[cfg(feature = "audio-file")]
+use {
+    crate::engine::audio_file::{AudioFile, AudioFileError},
+    std::path::Path,
+};
+use {
+    crate::value::{
+        types::{IsPrimitive, Primitive},
+        Value,
+    },
+    std::{collections::HashMap, ffi::c_void, fmt, sync::Arc},
+};
 
 /// Externals definitions for a Cmajor program.
 #[derive(Debug, Default)]
 pub struct Externals {
     pub(crate) variables: HashMap<String, Value>,
+    pub(crate) functions: Vec<ExternalFunctionDefinition>,
+    pub(crate) debug_sink: Option<DebugSink>,
+    #[cfg(feature = "audio-file")]
+    pub(crate) audio_files: HashMap<String, AudioFile>,
 }
 
+/// A destination for messages printed by the program with `rust::debug::print`.
+#[derive(Clone)]
+pub(crate) struct DebugSink(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl DebugSink {
+    pub(crate) fn write(&self, message: &str) {
+        (self.0)(message)
+    }
+}
+
+impl fmt::Debug for DebugSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DebugSink")
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExternalFunctionDefinition {
+    name: String,
+    signature: Vec<Primitive>,
+    ptr: *mut c_void,
+}
+
+// SAFETY: The pointer is to a function, which can be called from any thread.
+unsafe impl Send for ExternalFunctionDefinition {}
+unsafe impl Sync for ExternalFunctionDefinition {}
+
 impl Externals {
     /// Define an external variable that will be loaded into the engine.
+    ///
+    /// This replaces any audio file loaded into a variable with the same name.
     pub fn set_variable(&mut self, name: impl AsRef<str>, value: impl Into<Value>) {
-        self.variables
-            .insert(name.as_ref().to_string(), value.into());
+        let name = name.as_ref();
+        #[cfg(feature = "audio-file")]
+        self.audio_files.remove(name);
+        self.variables.insert(name.to_string(), value.into());
     }
 
     /// Define an external variable that will be loaded into the engine.
@@ -18,4 +64,303 @@ impl Externals {
         self.set_variable(name, value);
         self
     }
+
+    /// Define an external function that can be called by the program.
+    ///
+    /// Functions are looked up by name and parameter types. Cmajor doesn't say which return type
+    /// it expects, so the return type isn't checked: it must match the external function declared
+    /// in the program. Functions defined here take precedence over the built-in `rust::`
+    /// functions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::engine::Externals;
+    /// extern "C" fn log_value(value: f32) {
+    ///     println!("{value}");
+    /// }
+    ///
+    /// extern "C" fn clamp(value: i32, min: i32, max: i32) -> i32 {
+    ///     value.clamp(min, max)
+    /// }
+    ///
+    /// let externals = Externals::default()
+    ///     .with_function("rust::log", log_value as extern "C" fn(f32))
+    ///     .with_function("rust::clamp", clamp as extern "C" fn(i32, i32, i32) -> i32);
+    /// ```
+    pub fn set_function(&mut self, name: impl AsRef<str>, function: impl ExternalFunction) {
+        let name = name.as_ref().to_string();
+        let signature = function.signature();
+
+        self.functions
+            .retain(|definition| definition.name != name || definition.signature != signature);
+        self.functions.push(ExternalFunctionDefinition {
+            name,
+            signature,
+            ptr: function.into_ptr(),
+        });
+    }
+
+    /// Define an external function that can be called by the program.
+    pub fn with_function(mut self, name: impl AsRef<str>, function: impl ExternalFunction) -> Self {
+        self.set_function(name, function);
+        self
+    }
+
+    /// Load a WAV file into an external variable.
+    ///
+    /// The file is read straight away, and converted into the type the program declares for the
+    /// variable when the program is loaded. Supported types are arrays of `float32` samples (for
+    /// mono files), arrays of `float32[N]` frames (for files with `N` channels), or a struct with
+    /// a `frames` field of one of those types and an optional `sampleRate` field.
+    ///
+    /// This replaces any variable defined with the same name.
+    #[cfg(feature = "audio-file")]
+    pub fn set_audio_file(
+        &mut self,
+        name: impl AsRef<str>,
+        path: impl AsRef<Path>,
+    ) -> Result<(), AudioFileError> {
+        let audio_file = AudioFile::read(path)?;
+        self.variables.remove(name.as_ref());
+        self.audio_files
+            .insert(name.as_ref().to_string(), audio_file);
+        Ok(())
+    }
+
+    /// Load a WAV file into an external variable.
+    ///
+    /// See [`Externals::set_audio_file`].
+    #[cfg(feature = "audio-file")]
+    pub fn with_audio_file(
+        mut self,
+        name: impl AsRef<str>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, AudioFileError> {
+        self.set_audio_file(name, path)?;
+        Ok(self)
+    }
+
+    /// Redirect messages printed by the program with `rust::debug::print` to the given sink.
+    ///
+    /// By default, messages are printed to stdout. The sink is called from the thread that
+    /// advances the performer.
+    pub fn set_debug_sink(&mut self, sink: impl Fn(&str) + Send + Sync + 'static) {
+        self.debug_sink = Some(DebugSink(Arc::new(sink)));
+    }
+
+    /// Redirect messages printed by the program with `rust::debug::print` to the given sink.
+    pub fn with_debug_sink(mut self, sink: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.set_debug_sink(sink);
+        self
+    }
+
+    /// Merge another set of externals into this one.
+    ///
+    /// Definitions in `other` take precedence over those with the same name (and, for functions,
+    /// the same signature) in `self`, which makes it possible to layer overrides on top of
+    /// defaults. A variable in `other` replaces an audio file with the same name in `self`, and
+    /// vice versa.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::engine::Externals;
+    /// let defaults = Externals::default()
+    ///     .with_variable("gain", 0.5_f32)
+    ///     .with_variable("voices", 8);
+    /// let overrides = Externals::default().with_variable("gain", 1.0_f32);
+    ///
+    /// let externals = defaults.merge(overrides);
+    /// assert!(externals.contains("gain") && externals.contains("voices"));
+    /// ```
+    pub fn merge(mut self, other: Externals) -> Self {
+        #[cfg(feature = "audio-file")]
+        for name in other.variables.keys() {
+            self.audio_files.remove(name);
+        }
+        self.variables.extend(other.variables);
+        for definition in other.functions {
+            self.functions.retain(|existing| {
+                existing.name != definition.name || existing.signature != definition.signature
+            });
+            self.functions.push(definition);
+        }
+        if other.debug_sink.is_some() {
+            self.debug_sink = other.debug_sink;
+        }
+        #[cfg(feature = "audio-file")]
+        for (name, audio_file) in other.audio_files {
+            self.variables.remove(&name);
+            self.audio_files.insert(name, audio_file);
+        }
+        self
+    }
+
+    /// Returns `true` if an external variable with the given name has been defined.
+    pub fn contains(&self, name: impl AsRef<str>) -> bool {
+        let name = name.as_ref();
+
+        #[cfg(feature = "audio-file")]
+        if self.audio_files.contains_key(name) {
+            return true;
+        }
+
+        self.variables.contains_key(name)
+    }
+
+    /// The names of the external variables that have been defined, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        let names = self.variables.keys();
+
+        #[cfg(feature = "audio-file")]
+        let names = names.chain(self.audio_files.keys());
+
+        names.map(String::as_str)
+    }
+
+    pub(crate) fn get_function(&self, name: &str, signature: &[Primitive]) -> Option<*mut c_void> {
+        self.functions
+            .iter()
+            .find(|definition| definition.name == name && definition.signature == signature)
+            .map(|definition| definition.ptr)
+    }
+}
+
+/// A function that can be called from a Cmajor program.
+///
+/// Implemented for `extern "C"` function pointers whose parameters are primitive types, and that
+/// return either nothing or a primitive type.
+pub trait ExternalFunction: sealed::Sealed {
+    #[doc(hidden)]
+    fn signature(&self) -> Vec<Primitive>;
+
+    #[doc(hidden)]
+    fn into_ptr(self) -> *mut c_void;
+}
+
+/// A type that can be returned from an external function.
+#[doc(hidden)]
+pub trait ExternalReturnType {}
+
+impl ExternalReturnType for () {}
+
+impl<T> ExternalReturnType for T where T: IsPrimitive {}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_external_function {
+    ($($arg:ident),*) => {
+        impl<R, $($arg),*> sealed::Sealed for extern "C" fn($($arg),*) -> R
+        where
+            R: ExternalReturnType,
+            $($arg: IsPrimitive),*
+        {
+        }
+
+        impl<R, $($arg),*> ExternalFunction for extern "C" fn($($arg),*) -> R
+        where
+            R: ExternalReturnType,
+            $($arg: IsPrimitive),*
+        {
+            fn signature(&self) -> Vec<Primitive> {
+                vec![$($arg::PRIMITIVE),*]
+            }
+
+            fn into_ptr(self) -> *mut c_void {
+                self as *mut c_void
+            }
+        }
+    };
+}
+
+impl_external_function!();
+impl_external_function!(A);
+impl_external_function!(A, B);
+impl_external_function!(A, B, C);
+impl_external_function!(A, B, C, D);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    extern "C" fn first(_: i32) {}
+
+    extern "C" fn second(_: i32) {}
+
+    #[test]
+    fn externals_can_be_merged() {
+        let defaults = Externals::default()
+            .with_variable("a", 1)
+            .with_variable("b", 2)
+            .with_function("f", first as extern "C" fn(i32));
+        let overrides = Externals::default()
+            .with_variable("b", 3)
+            .with_function("f", second as extern "C" fn(i32));
+
+        let externals = defaults.merge(overrides);
+
+        let mut names: Vec<_> = externals.names().collect();
+        names.sort();
+        assert_eq!(names, ["a", "b"]);
+        assert!(!externals.contains("c"));
+        assert_eq!(externals.variables["b"], Value::from(3));
+        assert_eq!(
+            externals.get_function("f", &[Primitive::Int32]),
+            Some(second as *mut c_void)
+        );
+        assert_eq!(externals.functions.len(), 1);
+    }
+
+    #[cfg(feature = "audio-file")]
+    #[test]
+    fn merged_variables_and_audio_files_replace_each_other() {
+        let path = std::env::temp_dir().join(format!(
+            "cmajor-merged-audio-file-{}.wav",
+            std::process::id()
+        ));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44_100,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        writer.write_sample(0.5_f32).unwrap();
+        writer.finalize().unwrap();
+
+        let with_variable = || Externals::default().with_variable("x", 1);
+        let with_audio_file = || Externals::default().with_audio_file("x", &path).unwrap();
+
+        let externals = with_variable().merge(with_audio_file());
+        assert_eq!(externals.names().collect::<Vec<_>>(), ["x"]);
+        assert!(externals.variables.is_empty());
+
+        let externals = with_audio_file().merge(with_variable());
+        assert_eq!(externals.names().collect::<Vec<_>>(), ["x"]);
+        assert!(externals.audio_files.is_empty());
+
+        let externals = with_variable().with_audio_file("x", &path).unwrap();
+        assert!(externals.variables.is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn external_functions_can_return_values() {
+        extern "C" fn clamp(value: i32, min: i32, max: i32) -> i32 {
+            value.clamp(min, max)
+        }
+
+        let function = clamp as extern "C" fn(i32, i32, i32) -> i32;
+        assert_eq!(function.signature(), [Primitive::Int32; 3]);
+
+        let externals = Externals::default().with_function("clamp", function);
+        assert_eq!(
+            externals.get_function("clamp", &[Primitive::Int32; 3]),
+            Some(clamp as *mut c_void)
+        );
+    }
 }
@@ -1,24 +1,37 @@
 //! The Cmajor engine for compiling programs.
 
 mod annotation;
+#[cfg(feature = "audio-file")]
+mod audio_file;
 mod externals;
 mod program_details;
 
+#[cfg(feature = "audio-file")]
+pub use audio_file::AudioFileError;
+pub(crate) use externals::DebugSink;
 use {
     crate::{
+        diagnostic::Diagnostics,
         endpoint::{EndpointHandle, EndpointInfo},
-        ffi::EnginePtr,
+        ffi::{CacheDatabasePtr, EnginePtr},
         performer::{Endpoint, EndpointError, EndpointType, OutputEvent, Performer},
         program::Program,
     },
     std::{
         borrow::Cow,
         collections::HashMap,
+        convert::Infallible,
         ffi::{CStr, CString},
+        path::Path,
         slice::Split,
+        str::FromStr,
     },
 };
-pub use {annotation::Annotation, externals::Externals, program_details::ProgramDetails};
+pub use {
+    annotation::{Annotation, AnnotationExt},
+    externals::{ExternalFunction, Externals},
+    program_details::ProgramDetails,
+};
 
 /// The set of supported engine types.
 pub struct EngineTypes<'a> {
@@ -46,20 +59,42 @@ impl<'a> Iterator for EngineTypes<'a> {
 }
 
 /// An engine type.
+///
+/// Engine types can be created from their names, e.g. `EngineType::from("llvm")`. Whether the
+/// library supports the engine type is only checked when an engine is created with
+/// [`Cmajor::create_engine`](crate::Cmajor::create_engine).
 #[derive(Clone)]
 pub struct EngineType(String);
 
 impl EngineType {
-    pub(crate) fn to_str(&self) -> &str {
+    /// The name of the engine type, e.g. `llvm`.
+    pub fn name(&self) -> &str {
         &self.0
     }
+}
 
-    pub(crate) fn default_engine_type() -> Self {
+impl Default for EngineType {
+    /// The library's default engine type (LLVM JIT).
+    fn default() -> Self {
         // Empty string is the default engine type.
         Self(String::new())
     }
 }
 
+impl From<&str> for EngineType {
+    fn from(name: &str) -> Self {
+        Self(name.to_owned())
+    }
+}
+
+impl FromStr for EngineType {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(name))
+    }
+}
+
 impl PartialEq<str> for EngineType {
     fn eq(&self, other: &str) -> bool {
         self.0 == other
@@ -74,36 +109,113 @@ impl std::fmt::Debug for EngineType {
 
 /// A builder for a [`Engine`].
 pub struct EngineBuilder {
-    pub(crate) sample_rate: f64,
-    pub(crate) engine: Engine<Idle>,
+    sample_rate: f64,
+    main_processor: Option<String>,
+    max_frequency: Option<f64>,
+    max_block_size: Option<u32>,
+    max_state_size: Option<u64>,
+    session_id: Option<i32>,
+    engine: Engine<Idle>,
 }
 
 impl EngineBuilder {
+    pub(crate) fn new(engine: Engine<Idle>) -> Self {
+        Self {
+            sample_rate: 0.0,
+            main_processor: None,
+            max_frequency: None,
+            max_block_size: None,
+            max_state_size: None,
+            session_id: None,
+            engine,
+        }
+    }
+
     /// Set the sample rate (in Hertz) to use.
     pub fn with_sample_rate(mut self, sample_rate: f64) -> Self {
         self.sample_rate = sample_rate;
         self
     }
 
+    /// Set the (fully qualified) name of the processor to use as the program's entry point.
+    ///
+    /// By default, the engine picks the processor marked as `[[ main ]]`, or the last processor
+    /// declared in the program.
+    pub fn with_main_processor(mut self, name: impl Into<String>) -> Self {
+        self.main_processor = Some(name.into());
+        self
+    }
+
+    /// Set the maximum sample rate (in Hertz) the program may be run at.
+    pub fn with_max_frequency(mut self, max_frequency: f64) -> Self {
+        self.max_frequency = Some(max_frequency);
+        self
+    }
+
+    /// Set the maximum number of frames that can be rendered in a single block.
+    pub fn with_max_block_size(mut self, max_block_size: u32) -> Self {
+        self.max_block_size = Some(max_block_size);
+        self
+    }
+
+    /// Set the maximum size (in bytes) of the program's state.
+    pub fn with_max_state_size(mut self, max_state_size: u64) -> Self {
+        self.max_state_size = Some(max_state_size);
+        self
+    }
+
+    /// Set the session ID, which the program can read via `processor.session`.
+    pub fn with_session_id(mut self, session_id: i32) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
     /// Build the engine.
     pub fn build(self) -> Engine {
         let Self {
             sample_rate,
+            main_processor,
+            max_frequency,
+            max_block_size,
+            max_state_size,
+            session_id,
             engine,
         } = self;
 
-        let build_settings = CString::new(
-            serde_json::json!(
-                {
-                    "frequency": sample_rate
-                }
-            )
-            .to_string(),
-        )
-        .expect("failed to convert build settings to C string");
+        let mut build_settings = serde_json::json!(
+            {
+                "frequency": sample_rate
+            }
+        );
+
+        if let Some(main_processor) = main_processor {
+            build_settings["mainProcessor"] = main_processor.into();
+        }
+
+        if let Some(max_frequency) = max_frequency {
+            build_settings["maxFrequency"] = max_frequency.into();
+        }
+
+        if let Some(max_block_size) = max_block_size {
+            build_settings["maxBlockSize"] = max_block_size.into();
+        }
+
+        if let Some(max_state_size) = max_state_size {
+            build_settings["maxStateSize"] = max_state_size.into();
+        }
+
+        if let Some(session_id) = session_id {
+            build_settings["sessionID"] = session_id.into();
+        }
+
+        let build_settings = CString::new(build_settings.to_string())
+            .expect("failed to convert build settings to C string");
 
         engine.inner.set_build_settings(build_settings.as_c_str());
-        engine
+        Engine {
+            sample_rate,
+            ..engine
+        }
     }
 }
 
@@ -111,19 +223,47 @@ impl EngineBuilder {
 #[derive(Debug)]
 pub struct Engine<State = Idle> {
     inner: EnginePtr,
+    sample_rate: f64,
     state: State,
 }
 
 /// An error from the engine.
 #[derive(thiserror::Error, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Error {
     /// The engine failed to load the program.
-    #[error("Failed to load program: {:#?}", .0)]
-    FailedToLoad(Engine<Idle>, String),
+    #[error("Failed to load program: {:#?}", .1)]
+    FailedToLoad(Engine<Idle>, ErrorDetails),
 
     /// The engine failed to link the program.
-    #[error("Failed to link program: {:#?}", .0)]
-    FailedToLink(Engine<Loaded>, String),
+    #[error("Failed to link program: {:#?}", .1)]
+    FailedToLink(Engine<Loaded>, ErrorDetails),
+}
+
+/// The details of why the engine failed to load or link a program.
+#[derive(Debug)]
+pub enum ErrorDetails {
+    /// Diagnostic messages reported by the engine.
+    Diagnostics(Diagnostics),
+
+    /// A message from the engine that couldn't be parsed as diagnostics.
+    Message(String),
+}
+
+impl ErrorDetails {
+    fn new(error: &str) -> Self {
+        serde_json::from_str(error)
+            .map(Self::Diagnostics)
+            .unwrap_or_else(|_| Self::Message(error.to_owned()))
+    }
+
+    /// Returns the diagnostic messages, if the error could be parsed as diagnostics.
+    pub fn diagnostics(&self) -> Option<&Diagnostics> {
+        match self {
+            Self::Diagnostics(diagnostics) => Some(diagnostics),
+            Self::Message(_) => None,
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -135,7 +275,9 @@ pub struct Idle;
 pub struct Loaded {
     program_details: ProgramDetails,
     endpoints: HashMap<EndpointHandle, EndpointInfo>,
+    handles: HashMap<String, EndpointHandle>,
     console: Option<Endpoint<OutputEvent>>,
+    debug_sink: Option<DebugSink>,
 }
 
 #[doc(hidden)]
@@ -143,27 +285,33 @@ pub struct Loaded {
 pub struct Linked {
     endpoints: HashMap<EndpointHandle, EndpointInfo>,
     console: Option<Endpoint<OutputEvent>>,
+    debug_sink: Option<DebugSink>,
 }
 
 impl Engine<Idle> {
     pub(crate) fn new(engine: EnginePtr) -> Self {
         Self {
             inner: engine,
+            sample_rate: 0.0,
             state: Idle,
         }
     }
 
     /// Load a program into the engine.
+    #[allow(clippy::result_large_err)]
     pub fn load(self, program: &Program) -> Result<Engine<Loaded>, Error> {
         self.load_with_externals(program, Externals::default())
     }
 
     /// Load a program into the engine and resolve external definitions.
+    #[allow(clippy::result_large_err)]
     pub fn load_with_externals(
         self,
         program: &Program,
         externals: Externals,
     ) -> Result<Engine<Loaded>, Error> {
+        let debug_sink = externals.debug_sink.clone();
+
         match self.inner.load(&program.inner, externals) {
             Ok(_) => {
                 let program_details = self
@@ -176,16 +324,19 @@ impl Engine<Idle> {
 
                 let mut loaded = Engine {
                     inner: self.inner,
+                    sample_rate: self.sample_rate,
                     state: Loaded {
                         program_details,
                         endpoints: HashMap::default(),
+                        handles: HashMap::default(),
                         console: None,
+                        debug_sink,
                     },
                 };
                 loaded.state.console = loaded.endpoint("console").ok();
                 Ok(loaded)
             }
-            Err(error) => Err(Error::FailedToLoad(self, error.to_str().to_owned())),
+            Err(error) => Err(Error::FailedToLoad(self, ErrorDetails::new(&error))),
         }
     }
 }
@@ -203,11 +354,11 @@ impl Engine<Loaded> {
             .program_details
             .endpoints()
             .find(|endpoint| endpoint.id() == id)
+            .cloned()
             .ok_or(EndpointError::EndpointDoesNotExist)?;
 
         let handle = self
-            .inner
-            .get_endpoint_handle(id)
+            .endpoint_handle(id)
             .ok_or(EndpointError::EndpointDoesNotExist)?;
 
         self.state.endpoints.insert(handle, info.clone());
@@ -215,48 +366,222 @@ impl Engine<Loaded> {
         EndpointType::make(handle, info)
     }
 
+    /// Returns an endpoint for a handle, such as one that was saved with [`Endpoint::handle`].
+    ///
+    /// Fails if no endpoint in the program has the handle, or if its type doesn't match.
+    pub fn endpoint_for_handle<T>(
+        &mut self,
+        handle: EndpointHandle,
+    ) -> Result<Endpoint<T>, EndpointError>
+    where
+        T: EndpointType,
+    {
+        let infos: Vec<_> = self.state.program_details.endpoints().cloned().collect();
+
+        for info in infos {
+            if self.endpoint_handle(info.id().as_ref()) == Some(handle) {
+                self.state.endpoints.insert(handle, info.clone());
+                return EndpointType::make(handle, info);
+            }
+        }
+
+        Err(EndpointError::EndpointDoesNotExist)
+    }
+
+    /// Returns the handles of all the endpoints in the program, along with their details.
+    ///
+    /// The handles remain valid once the engine is linked, so can be used to look up endpoint
+    /// information from the [`Performer`].
+    pub fn endpoints(&mut self) -> impl Iterator<Item = (EndpointInfo, EndpointHandle)> {
+        let infos: Vec<_> = self.state.program_details.endpoints().cloned().collect();
+
+        let mut endpoints = Vec::with_capacity(infos.len());
+        for info in infos {
+            if let Some(handle) = self.endpoint_handle(info.id().as_ref()) {
+                self.state.endpoints.insert(handle, info.clone());
+                endpoints.push((info, handle));
+            }
+        }
+
+        endpoints.into_iter()
+    }
+
+    /// Look up the handle for an endpoint, caching it to avoid repeated calls into the library.
+    fn endpoint_handle(&mut self, id: &str) -> Option<EndpointHandle> {
+        if let Some(handle) = self.state.handles.get(id) {
+            return Some(*handle);
+        }
+
+        let handle = self.inner.get_endpoint_handle(id)?;
+        self.state.handles.insert(id.to_owned(), handle);
+        Some(handle)
+    }
+
     /// Returns the details of the program loaded into the engine.
     pub fn program_details(&self) -> &ProgramDetails {
         &self.state.program_details
     }
 
+    /// Returns the IDs of all the endpoints in the loaded program.
+    pub fn endpoint_ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.state
+            .program_details
+            .endpoints()
+            .map(|endpoint| endpoint.id().as_ref())
+    }
+
+    /// Returns the log from the last build, which may contain warnings or advice from the
+    /// compiler.
+    pub fn build_log(&self) -> Option<String> {
+        build_log(&self.inner)
+    }
+
     /// Link the program loaded into the engine.
+    #[allow(clippy::result_large_err)]
     pub fn link(self) -> Result<Engine<Linked>, Error> {
-        match self.inner.link() {
+        self.link_with_cache_database(None)
+    }
+
+    /// Link the program loaded into the engine, caching the compiled program in a directory.
+    ///
+    /// Linking the same program again with the same build settings reloads it from the cache
+    /// rather than compiling it again. The engine decides what to cache, and entries are keyed
+    /// by the program and its build settings, so a changed program is simply compiled afresh.
+    #[allow(clippy::result_large_err)]
+    pub fn link_with_cache(self, directory: impl AsRef<Path>) -> Result<Engine<Linked>, Error> {
+        let cache_database = CacheDatabasePtr::new(directory);
+        self.link_with_cache_database(Some(&cache_database))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn link_with_cache_database(
+        self,
+        cache_database: Option<&CacheDatabasePtr>,
+    ) -> Result<Engine<Linked>, Error> {
+        match self.inner.link(cache_database) {
             Ok(_) => {
                 let linked = Linked {
                     endpoints: self.state.endpoints,
                     console: self.state.console,
+                    debug_sink: self.state.debug_sink,
                 };
                 Ok(Engine {
                     inner: self.inner,
+                    sample_rate: self.sample_rate,
                     state: linked,
                 })
             }
-            Err(error) => Err(Error::FailedToLink(self, error.to_str().to_owned())),
+            Err(error) => Err(Error::FailedToLink(self, ErrorDetails::new(error.to_str()))),
         }
     }
 }
 
 impl Engine<Linked> {
+    /// Returns the log from the last build, which may contain warnings or advice from the
+    /// compiler.
+    pub fn build_log(&self) -> Option<String> {
+        build_log(&self.inner)
+    }
+
+    /// Unload the current program and load a new one in its place.
+    ///
+    /// The engine's build settings (such as the sample rate) are kept, so the new program can be
+    /// linked straight away. Use [`Engine::unload`] and [`Engine::load_with_externals`] if the
+    /// new program needs externals.
+    #[allow(clippy::result_large_err)]
+    pub fn reload(self, program: &Program) -> Result<Engine<Loaded>, Error> {
+        self.unload().load(program)
+    }
+
     /// Create a performer for the linked program.
     pub fn performer(&self) -> Performer {
         Performer::new(
             self.inner.create_performer(),
             self.state.endpoints.clone(),
             self.state.console,
+            self.state.debug_sink.clone(),
+            self.sample_rate,
         )
     }
 }
 
+fn build_log(engine: &EnginePtr) -> Option<String> {
+    engine
+        .last_build_log()
+        .map(|log| log.to_str().to_owned())
+        .filter(|log| !log.is_empty())
+}
+
 impl<T> Engine<T> {
+    /// Returns the sample rate the engine was built with, if one was set.
+    pub fn sample_rate(&self) -> Option<f64> {
+        (self.sample_rate > 0.0).then_some(self.sample_rate)
+    }
+
+    /// Convert a number of frames into a duration in seconds, at the engine's sample rate.
+    ///
+    /// Returns `None` if the engine was built without a sample rate.
+    pub fn frames_to_seconds(&self, frames: u64) -> Option<f64> {
+        self.sample_rate()
+            .map(|sample_rate| frames as f64 / sample_rate)
+    }
+
+    /// Convert a duration in seconds into the nearest number of frames, at the engine's sample
+    /// rate.
+    ///
+    /// Returns `None` if the engine was built without a sample rate.
+    pub fn seconds_to_frames(&self, seconds: f64) -> Option<u64> {
+        self.sample_rate()
+            .map(|sample_rate| (seconds * sample_rate).round() as u64)
+    }
+
     /// Unload the program, resetting the engine.
     pub fn unload(self) -> Engine<Idle> {
         self.inner.unload();
 
         Engine {
             inner: self.inner,
+            sample_rate: self.sample_rate,
             state: Idle,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn error_details_are_parsed_as_diagnostics() {
+        let json = r#"
+            {
+                "severity": "error",
+                "message": "Cannot find symbol 'foo'",
+                "fileName": "",
+                "lineNumber": 3,
+                "columnNumber": 5,
+                "sourceLine": "",
+                "annotatedLine": "",
+                "fullDescription": "3:5: error: Cannot find symbol 'foo'"
+            }
+        "#;
+
+        let details = ErrorDetails::new(json);
+        let diagnostics = details.diagnostics().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+
+        let details = ErrorDetails::new("something went wrong");
+        assert!(
+            matches!(details, ErrorDetails::Message(message) if message == "something went wrong")
+        );
+    }
+
+    #[test]
+    fn engine_types_can_be_named() {
+        assert_eq!(EngineType::default().name(), "");
+        assert_eq!(EngineType::from("llvm").name(), "llvm");
+
+        let engine_type: EngineType = "wasm".parse().unwrap();
+        assert_eq!(engine_type.name(), "wasm");
+    }
+}
@@ -1,8 +1,8 @@
 use {
     crate::{
         endpoint::{
-            EndpointDirection, EndpointId, EndpointInfo, EventEndpoint, StreamEndpoint,
-            ValueEndpoint,
+            EndpointDirection, EndpointId, EndpointInfo, EndpointKind, EventEndpoint,
+            StreamEndpoint, ValueEndpoint,
         },
         ffi::types::{TypeDescription, TypeDescriptionError},
         value::types::Type,
@@ -12,41 +12,110 @@ use {
         Deserialize, Deserializer,
     },
     serde_json::{Map as JsonMap, Value as JsonValue},
-    std::{fmt::Formatter, iter::repeat},
+    std::fmt::Formatter,
 };
 
 /// Details about a Cmajor program.
 #[derive(Debug, Deserialize)]
+#[serde(try_from = "JsonValue")]
 pub struct ProgramDetails {
+    inputs: Vec<EndpointInfo>,
+    outputs: Vec<EndpointInfo>,
+    main_processor: String,
+    extra: JsonMap<String, JsonValue>,
+    json: JsonValue,
+}
+
+#[derive(Deserialize)]
+struct ProgramDetailsJson {
     inputs: Vec<EndpointDetails>,
     outputs: Vec<EndpointDetails>,
     #[serde(rename = "mainProcessor")]
     main_processor: String,
     #[serde(flatten)]
-    _extra: JsonMap<String, JsonValue>,
+    extra: JsonMap<String, JsonValue>,
+}
+
+impl TryFrom<JsonValue> for ProgramDetails {
+    type Error = serde_json::Error;
+
+    fn try_from(json: JsonValue) -> Result<Self, Self::Error> {
+        let details = ProgramDetailsJson::deserialize(&json)?;
+
+        Ok(Self {
+            inputs: make_endpoints(&details.inputs, EndpointDirection::Input).collect(),
+            outputs: make_endpoints(&details.outputs, EndpointDirection::Output).collect(),
+            main_processor: details.main_processor,
+            extra: details.extra,
+            json,
+        })
+    }
 }
 
 impl ProgramDetails {
+    /// Returns the program details as they were reported by the engine, as JSON.
+    ///
+    /// This includes any details that aren't modelled by [`ProgramDetails`], which makes it
+    /// useful for forwarding the details to other tools.
+    pub fn as_json(&self) -> &JsonValue {
+        &self.json
+    }
+
+    /// Returns the latency (in frames) declared by the program, if any.
+    pub fn latency(&self) -> Option<f64> {
+        self.extra.get("latency").and_then(JsonValue::as_f64)
+    }
+
+    /// Returns the sample rate (in Hertz) the program was built for, if reported.
+    pub fn declared_frequency(&self) -> Option<f64> {
+        self.extra.get("frequency").and_then(JsonValue::as_f64)
+    }
+
     /// Returns the name of the processor to be used as an entry point.
     pub fn main_processor(&self) -> &str {
         &self.main_processor
     }
 
     /// Returns an iterator over all the endpoints in the program.
-    pub fn endpoints(&self) -> impl Iterator<Item = EndpointInfo> + '_ {
-        let inputs = self.inputs.iter().zip(repeat(EndpointDirection::Input));
-        let outputs = self.outputs.iter().zip(repeat(EndpointDirection::Output));
-
-        inputs.chain(outputs).filter_map(|(details, direction)| {
-            match try_make_endpoint(details, direction) {
-                Ok(endpoint) => Some(endpoint),
-                Err(err) => {
-                    eprintln!("failed to parse endpoint: {:?}", err);
-                    None
-                }
+    pub fn endpoints(&self) -> impl Iterator<Item = &EndpointInfo> {
+        self.inputs().chain(self.outputs())
+    }
+
+    /// Returns the direction and kind of the endpoint with the given ID, if it exists.
+    ///
+    /// This can be used to pick the right type of [`Endpoint`](crate::performer::Endpoint) to
+    /// request from the engine for an endpoint that is only known by its ID.
+    pub fn endpoint_kind(&self, id: impl AsRef<str>) -> Option<(EndpointDirection, EndpointKind)> {
+        let id = id.as_ref();
+        self.endpoints()
+            .find(|endpoint| endpoint.id() == id)
+            .map(|endpoint| (endpoint.direction(), endpoint.kind()))
+    }
+
+    /// Returns an iterator over the program's input endpoints.
+    pub fn inputs(&self) -> impl Iterator<Item = &EndpointInfo> {
+        self.inputs.iter()
+    }
+
+    /// Returns an iterator over the program's output endpoints.
+    pub fn outputs(&self) -> impl Iterator<Item = &EndpointInfo> {
+        self.outputs.iter()
+    }
+}
+
+fn make_endpoints(
+    details: &[EndpointDetails],
+    direction: EndpointDirection,
+) -> impl Iterator<Item = EndpointInfo> + '_ {
+    details
+        .iter()
+        .filter_map(move |details| match try_make_endpoint(details, direction) {
+            Ok(endpoint) => Some(endpoint),
+            Err(err) => {
+                eprintln!("failed to parse endpoint: {:?}", err);
+                None
             }
         })
-    }
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -68,7 +137,7 @@ struct EndpointDetails {
     annotation: Option<JsonMap<String, JsonValue>>,
 
     #[serde(flatten)]
-    _extra: JsonMap<String, JsonValue>,
+    extra: JsonMap<String, JsonValue>,
 }
 
 #[derive(Debug, Copy, Clone, Deserialize, PartialEq)]
@@ -89,13 +158,13 @@ fn try_make_endpoint(
         endpoint_type,
         value_type,
         annotation,
-        ..
+        extra,
     }: &EndpointDetails,
     direction: EndpointDirection,
 ) -> Result<EndpointInfo, TypeDescriptionError> {
     let annotation = annotation.clone().unwrap_or_default();
 
-    Ok(match endpoint_type {
+    let endpoint: EndpointInfo = match endpoint_type {
         EndpointVariant::Stream => {
             if value_type.len() != 1 {
                 return Err(TypeDescriptionError::UnexpectedNumberOfTypes);
@@ -113,7 +182,9 @@ fn try_make_endpoint(
 
             ValueEndpoint::new(id.clone(), direction, value_type[0].clone(), annotation).into()
         }
-    })
+    };
+
+    Ok(endpoint.with_extras(extra.clone()))
 }
 
 fn deserialize_data_type<'de, D>(deserializer: D) -> Result<Vec<Type>, D::Error>
@@ -181,6 +252,27 @@ mod test {
         assert_eq!(details.value_type, vec![Type::Float32]);
     }
 
+    #[test]
+    fn unmodelled_endpoint_properties_are_kept() {
+        let json = r#"
+            {
+                "endpointID": "midiIn",
+                "endpointType": "event",
+                "dataType": { "type": "int32" },
+                "purpose": "midi in"
+            }
+        "#;
+
+        let details: EndpointDetails = serde_json::from_str(json).unwrap();
+        let endpoint = try_make_endpoint(&details, EndpointDirection::Input).unwrap();
+
+        assert_eq!(
+            endpoint.extra("purpose").and_then(JsonValue::as_str),
+            Some("midi in")
+        );
+        assert_eq!(endpoint.extra("endpointID"), None);
+    }
+
     #[test]
     fn parse_an_endpoint_with_a_multiple_data_type() {
         let json = r#"
@@ -204,4 +296,79 @@ mod test {
         assert_eq!(details.endpoint_type, EndpointVariant::Event);
         assert_eq!(details.value_type, vec![Type::Float32, Type::Int32]);
     }
+
+    #[test]
+    fn latency_and_frequency_are_read_from_the_details() {
+        let json = r#"
+            {
+                "mainProcessor": "Test",
+                "inputs": [],
+                "outputs": [],
+                "latency": 32,
+                "frequency": 44100.0
+            }
+        "#;
+
+        let details: ProgramDetails = serde_json::from_str(json).unwrap();
+        assert_eq!(details.latency(), Some(32.0));
+        assert_eq!(details.declared_frequency(), Some(44_100.0));
+        assert_eq!(details.as_json()["latency"], 32);
+        assert_eq!(
+            details.as_json(),
+            &serde_json::from_str::<JsonValue>(json).unwrap()
+        );
+
+        let details: ProgramDetails =
+            serde_json::from_str(r#"{ "mainProcessor": "Test", "inputs": [], "outputs": [] }"#)
+                .unwrap();
+        assert_eq!(details.latency(), None);
+        assert_eq!(details.declared_frequency(), None);
+    }
+
+    #[test]
+    fn endpoints_can_be_filtered_by_direction() {
+        let json = r#"
+            {
+                "mainProcessor": "Test",
+                "inputs": [
+                    {
+                        "endpointID": "in",
+                        "endpointType": "value",
+                        "dataType": { "type": "int32" }
+                    }
+                ],
+                "outputs": [
+                    {
+                        "endpointID": "out",
+                        "endpointType": "stream",
+                        "dataType": { "type": "float32" }
+                    }
+                ]
+            }
+        "#;
+
+        let details: ProgramDetails = serde_json::from_str(json).unwrap();
+
+        let inputs: Vec<_> = details.inputs().collect();
+        assert_eq!(inputs.len(), 1);
+        assert!(inputs[0].is_input());
+        assert_eq!(inputs[0].id().as_ref(), "in");
+
+        let outputs: Vec<_> = details.outputs().collect();
+        assert_eq!(outputs.len(), 1);
+        assert!(outputs[0].is_output());
+        assert_eq!(outputs[0].id().as_ref(), "out");
+
+        assert_eq!(details.endpoints().count(), 2);
+
+        assert_eq!(
+            details.endpoint_kind("in"),
+            Some((EndpointDirection::Input, EndpointKind::Value))
+        );
+        assert_eq!(
+            details.endpoint_kind("out"),
+            Some((EndpointDirection::Output, EndpointKind::Stream))
+        );
+        assert_eq!(details.endpoint_kind("missing"), None);
+    }
 }
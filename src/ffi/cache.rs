@@ -0,0 +1,198 @@
+use std::{
+    ffi::{c_char, c_int, c_void, CStr},
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicI32, AtomicU32, Ordering},
+};
+
+#[repr(C)]
+struct CacheDatabaseVTable {
+    add_ref: unsafe extern "system" fn(*mut CacheDatabase) -> c_int,
+    release: unsafe extern "system" fn(*mut CacheDatabase) -> c_int,
+    ref_count: unsafe extern "system" fn(*const CacheDatabase) -> c_int,
+    store: unsafe extern "system" fn(*mut CacheDatabase, *const c_char, *const c_void, u64),
+    reload: unsafe extern "system" fn(*mut CacheDatabase, *const c_char, *mut c_void, u64) -> u64,
+}
+
+/// A reference-counted cache database that the engine can use to store compiled programs.
+#[repr(C)]
+struct CacheDatabase {
+    vtable: *const CacheDatabaseVTable,
+    ref_count: AtomicI32,
+    directory: PathBuf,
+}
+
+static VTABLE: CacheDatabaseVTable = CacheDatabaseVTable {
+    add_ref,
+    release,
+    ref_count,
+    store,
+    reload,
+};
+
+unsafe extern "system" fn add_ref(cache: *mut CacheDatabase) -> c_int {
+    let cache = unsafe { &*cache };
+    cache.ref_count.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+unsafe extern "system" fn release(cache: *mut CacheDatabase) -> c_int {
+    let ref_count = unsafe { &*cache }.ref_count.fetch_sub(1, Ordering::AcqRel) - 1;
+
+    if ref_count == 0 {
+        drop(unsafe { Box::from_raw(cache) });
+    }
+
+    ref_count
+}
+
+unsafe extern "system" fn ref_count(cache: *const CacheDatabase) -> c_int {
+    unsafe { &*cache }.ref_count.load(Ordering::Relaxed)
+}
+
+unsafe extern "system" fn store(
+    cache: *mut CacheDatabase,
+    key: *const c_char,
+    data: *const c_void,
+    size: u64,
+) {
+    let cache = unsafe { &*cache };
+    let key = unsafe { CStr::from_ptr(key) };
+    let data = unsafe { std::slice::from_raw_parts(data.cast::<u8>(), size as usize) };
+
+    // Failing to write to the cache just means the program will be compiled again next time.
+    let _ = cache.write_entry(key, data);
+}
+
+unsafe extern "system" fn reload(
+    cache: *mut CacheDatabase,
+    key: *const c_char,
+    dest: *mut c_void,
+    dest_size: u64,
+) -> u64 {
+    let cache = unsafe { &*cache };
+    let key = unsafe { CStr::from_ptr(key) };
+
+    let Ok(data) = fs::read(cache.path_for_key(key)) else {
+        return 0;
+    };
+
+    // If there's no destination, or it's too small, the caller is asking for the size.
+    if !dest.is_null() && dest_size >= data.len() as u64 {
+        unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), dest.cast(), data.len()) };
+    }
+
+    data.len() as u64
+}
+
+impl CacheDatabase {
+    /// The file for a key, named with the key's bytes in hex so that distinct keys never share
+    /// a file.
+    fn path_for_key(&self, key: &CStr) -> PathBuf {
+        let file_name: String = key
+            .to_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        self.directory.join(file_name)
+    }
+
+    /// Write an entry to a temporary file, then move it into place, so that an interrupted write
+    /// never leaves a truncated entry behind.
+    fn write_entry(&self, key: &CStr, data: &[u8]) -> std::io::Result<()> {
+        static NEXT_TEMP_FILE: AtomicU32 = AtomicU32::new(0);
+
+        fs::create_dir_all(&self.directory)?;
+
+        let path = self.path_for_key(key);
+        let temp_path = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        fs::write(&temp_path, data)
+            .and_then(|_| fs::rename(&temp_path, &path))
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&temp_path);
+            })
+    }
+}
+
+/// An owning reference to a cache database, backed by files in a directory.
+#[derive(Debug)]
+pub struct CacheDatabasePtr {
+    ptr: *mut CacheDatabase,
+}
+
+impl CacheDatabasePtr {
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        let cache = Box::new(CacheDatabase {
+            vtable: &VTABLE,
+            ref_count: AtomicI32::new(1),
+            directory: directory.as_ref().to_owned(),
+        });
+
+        Self {
+            ptr: Box::into_raw(cache),
+        }
+    }
+
+    pub fn get(&self) -> *mut c_void {
+        self.ptr.cast()
+    }
+}
+
+impl Drop for CacheDatabasePtr {
+    fn drop(&mut self) {
+        unsafe { release(self.ptr) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, std::ffi::CString};
+
+    #[test]
+    fn entries_are_stored_as_files() {
+        let directory = std::env::temp_dir().join(format!(
+            "cmajor-entries-are-stored-as-files-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&directory);
+        let cache = CacheDatabasePtr::new(&directory);
+        let ptr = cache.get().cast::<CacheDatabase>();
+        let key = CString::new("program/1234").unwrap();
+
+        let data = [1_u8, 2, 3, 4];
+        unsafe { store(ptr, key.as_ptr(), data.as_ptr().cast(), data.len() as u64) };
+        assert!(directory.join("70726f6772616d2f31323334").exists());
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+
+        let size = unsafe { reload(ptr, key.as_ptr(), std::ptr::null_mut(), 0) };
+        assert_eq!(size, 4);
+
+        let mut buffer = [0_u8; 4];
+        let size = unsafe { reload(ptr, key.as_ptr(), buffer.as_mut_ptr().cast(), 4) };
+        assert_eq!(size, 4);
+        assert_eq!(buffer, data);
+
+        let missing = CString::new("missing").unwrap();
+        assert_eq!(
+            unsafe { reload(ptr, missing.as_ptr(), std::ptr::null_mut(), 0) },
+            0
+        );
+
+        let similar = CString::new("program_1234").unwrap();
+        assert_eq!(
+            unsafe { reload(ptr, similar.as_ptr(), std::ptr::null_mut(), 0) },
+            0
+        );
+
+        unsafe { add_ref(ptr) };
+        assert_eq!(unsafe { ref_count(ptr) }, 2);
+        unsafe { release(ptr) };
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
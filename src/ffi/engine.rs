@@ -3,6 +3,7 @@ use {
         endpoint::EndpointHandle,
         engine::Externals,
         ffi::{
+            cache::CacheDatabasePtr,
             externals::get_external_function,
             performer::{Performer, PerformerPtr},
             program::{Program, ProgramPtr},
@@ -27,6 +28,10 @@ type RequestExternalVariableCallback = unsafe extern "system" fn(*mut c_void, *c
 #[derive(Debug, Deserialize)]
 struct RequestExternalVariableArgs {
     name: String,
+
+    #[cfg(feature = "audio-file")]
+    #[serde(rename = "type")]
+    ty: Option<TypeDescription>,
 }
 
 type RequestExternalFunctionCallback =
@@ -85,10 +90,11 @@ impl EnginePtr {
         unsafe { (self.vtable().set_build_settings)(self.ptr, build_settings.as_ptr()) };
     }
 
-    pub fn load(&self, program: &ProgramPtr, externals: Externals) -> Result<(), CmajorStringPtr> {
+    pub fn load(&self, program: &ProgramPtr, externals: Externals) -> Result<(), String> {
         let mut ctx = LoadContext {
             engine: self.clone(),
             externals,
+            errors: Vec::new(),
         };
         let ctx_ptr = std::ptr::addr_of_mut!(ctx);
 
@@ -107,7 +113,13 @@ impl EnginePtr {
             return Ok(());
         }
 
-        Err(unsafe { CmajorStringPtr::new(error) })
+        let error = unsafe { CmajorStringPtr::new(error) };
+        if !ctx.errors.is_empty() {
+            // Errors from resolving externals explain why the engine failed to load.
+            return Err(ctx.errors.join("\n"));
+        }
+
+        Err(error.to_str().to_owned())
     }
 
     pub fn unload(&self) {
@@ -123,6 +135,15 @@ impl EnginePtr {
         Some(unsafe { CmajorStringPtr::new(result) })
     }
 
+    pub fn last_build_log(&self) -> Option<CmajorStringPtr> {
+        let result = unsafe { (self.vtable().get_last_build_log)(self.ptr) };
+        if result.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CmajorStringPtr::new(result) })
+    }
+
     pub fn get_endpoint_handle(&self, id: impl AsRef<str>) -> Option<EndpointHandle> {
         let id = CString::new(id.as_ref()).expect("should not contain a null byte");
 
@@ -134,8 +155,10 @@ impl EnginePtr {
         }
     }
 
-    pub fn link(&self) -> Result<(), CmajorStringPtr> {
-        let cache_database = null_mut();
+    pub fn link(&self, cache_database: Option<&CacheDatabasePtr>) -> Result<(), CmajorStringPtr> {
+        let cache_database = cache_database
+            .map(CacheDatabasePtr::get)
+            .unwrap_or(null_mut());
         let error = unsafe { (self.vtable().link)(self.ptr, cache_database) };
 
         if error.is_null() {
@@ -186,6 +209,7 @@ impl Drop for EnginePtr {
 struct LoadContext {
     engine: EnginePtr,
     externals: Externals,
+    errors: Vec<String>,
 }
 
 extern "system" fn request_external_variable_callback(ctx: *mut c_void, args: *const c_char) {
@@ -207,13 +231,30 @@ extern "system" fn request_external_variable_callback(ctx: *mut c_void, args: *c
 
     let ctx = unsafe { &mut *(ctx as *mut LoadContext) };
 
-    if let Some(value) = ctx.externals.variables.get(args.name.as_str()) {
-        ctx.engine.set_external_variable(args.name.as_str(), value);
+    match ctx.externals.variables.get(args.name.as_str()) {
+        Some(value) => ctx.engine.set_external_variable(args.name.as_str(), value),
+        #[cfg(feature = "audio-file")]
+        None => load_audio_file(ctx, &args),
+        #[cfg(not(feature = "audio-file"))]
+        None => {}
+    }
+}
+
+#[cfg(feature = "audio-file")]
+fn load_audio_file(ctx: &mut LoadContext, args: &RequestExternalVariableArgs) {
+    let Some(audio_file) = ctx.externals.audio_files.get(args.name.as_str()) else {
+        return;
+    };
+
+    let ty = args.ty.as_ref().and_then(|ty| Type::try_from(ty).ok());
+    match audio_file.to_value(ty.as_ref()) {
+        Ok(value) => ctx.engine.set_external_variable(args.name.as_str(), &value),
+        Err(error) => ctx.errors.push(format!("{}: {error}", args.name)),
     }
 }
 
 extern "system" fn request_external_function_callback(
-    _ctx: *mut c_void,
+    ctx: *mut c_void,
     name: *const c_char,
     signature: *const c_char,
 ) -> *mut c_void {
@@ -221,8 +262,13 @@ extern "system" fn request_external_function_callback(
     let signature = unsafe { CStr::from_ptr(signature) };
     let name = name.to_str().expect("failed to parse function symbol name");
 
+    let ctx = unsafe { &*(ctx as *const LoadContext) };
+
     if let Ok(signature) = parse_function_signature(signature) {
-        return get_external_function(name, signature.as_slice());
+        return ctx
+            .externals
+            .get_function(name, signature.as_slice())
+            .unwrap_or_else(|| get_external_function(name, signature.as_slice()));
     }
 
     null_mut()
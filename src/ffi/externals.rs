@@ -1,5 +1,5 @@
 use {
-    crate::value::types::Primitive,
+    crate::{engine::DebugSink, value::types::Primitive},
     std::{any::Any, cell::RefCell, ffi::c_void, panic::UnwindSafe, ptr::null_mut},
 };
 
@@ -35,8 +35,30 @@ pub fn check_for_panic() {
     });
 }
 
+/// Run `f` with print externals redirected to `sink`, if one is given.
+pub fn with_debug_sink<R>(sink: Option<&DebugSink>, f: impl FnOnce() -> R) -> R {
+    let Some(sink) = sink else {
+        return f();
+    };
+
+    let previous = DEBUG_SINK.with(|current| current.replace(Some(sink.clone())));
+    let _restore = RestoreDebugSink(previous);
+    f()
+}
+
+/// Restores the previous debug sink when dropped, including when unwinding from a panic.
+struct RestoreDebugSink(Option<DebugSink>);
+
+impl Drop for RestoreDebugSink {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        DEBUG_SINK.with(|current| current.replace(previous));
+    }
+}
+
 thread_local! {
     static PANIC: RefCell<Option<Box<dyn Any + Send>>> = RefCell::new(None);
+    static DEBUG_SINK: RefCell<Option<DebugSink>> = const { RefCell::new(None) };
 }
 
 fn catch_unwind_and_store_panic<F: FnOnce() -> R + UnwindSafe, R>(f: F) {
@@ -75,8 +97,9 @@ make_assert_eq_fn!(rust_assert_eq_f64, f64);
 macro_rules! make_print_fn {
     ($name:ident, $t:ty) => {
         extern "C" fn $name(value: $t) {
-            catch_unwind_and_store_panic(|| {
-                println!("{}", value);
+            catch_unwind_and_store_panic(|| match DEBUG_SINK.with(|sink| sink.borrow().clone()) {
+                Some(sink) => sink.write(&value.to_string()),
+                None => println!("{}", value),
             });
         }
     };
@@ -87,3 +110,24 @@ make_print_fn!(rust_print_i32, i32);
 make_print_fn!(rust_print_i64, i64);
 make_print_fn!(rust_print_f32, f32);
 make_print_fn!(rust_print_f64, f64);
+
+#[cfg(test)]
+mod test {
+    use {super::*, crate::engine::Externals};
+
+    #[test]
+    fn debug_sink_is_restored_after_a_panic() {
+        let externals = Externals::default().with_debug_sink(|_| {});
+        let sink = externals.debug_sink.as_ref();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            with_debug_sink(sink, || {
+                assert!(DEBUG_SINK.with(|current| current.borrow().is_some()));
+                panic!("external panicked");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert!(DEBUG_SINK.with(|current| current.borrow().is_none()));
+    }
+}
@@ -1,12 +1,17 @@
 use {
-    crate::ffi::engine_factory::{EngineFactory, EngineFactoryPtr},
+    crate::{
+        ffi::engine_factory::{EngineFactory, EngineFactoryPtr},
+        LibraryError,
+    },
     program::Program,
     std::{
         ffi::{c_char, c_void, CStr},
         path::Path,
+        sync::Arc,
     },
 };
 
+mod cache;
 mod engine;
 mod engine_factory;
 mod performer;
@@ -16,12 +21,23 @@ mod externals;
 mod string;
 pub(crate) mod types;
 
-pub use {engine::EnginePtr, performer::PerformerPtr, program::ProgramPtr};
+pub use {
+    cache::CacheDatabasePtr, engine::EnginePtr, externals::with_debug_sink,
+    performer::PerformerPtr, program::ProgramPtr,
+};
 
+#[derive(Clone)]
 pub struct Library {
     ptr: *mut EntryPoints,
+    _library: Option<Arc<libloading::Library>>,
 }
 
+// SAFETY: The entry points are a process-wide singleton owned by the Cmajor library, and its
+// functions may be called from any thread. The shared library handle (if any) is kept alive for
+// as long as any clone of `Library` exists.
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
 type CMajorGetEntryPointsV10 = unsafe extern "C" fn() -> *mut c_void;
 
 #[cfg(feature = "static")]
@@ -34,6 +50,7 @@ impl Library {
     pub fn new() -> Self {
         Self {
             ptr: unsafe { cmajor_getEntryPointsStatic() }.cast(),
+            _library: None,
         }
     }
 
@@ -46,15 +63,21 @@ impl Library {
         }
     }
 
-    pub fn load(path_to_library: impl AsRef<Path>) -> Result<Self, libloading::Error> {
+    pub fn load(path_to_library: impl AsRef<Path>) -> Result<Self, LibraryError> {
         const LIBRARY_ENTRY_POINT: &[u8] = b"cmajor_getEntryPointsV10";
 
         let library = unsafe { libloading::Library::new(path_to_library.as_ref()) }?;
         let entry_point_fn: libloading::Symbol<CMajorGetEntryPointsV10> =
-            unsafe { library.get(LIBRARY_ENTRY_POINT)? };
+            match unsafe { library.get(LIBRARY_ENTRY_POINT) } {
+                Ok(entry_point_fn) => entry_point_fn,
+                Err(error) => return Err(unsupported_abi(&library).unwrap_or(error.into())),
+            };
+
+        let ptr = unsafe { entry_point_fn() }.cast();
 
         Ok(Self {
-            ptr: unsafe { entry_point_fn() }.cast(),
+            ptr,
+            _library: Some(Arc::new(library)),
         })
     }
 
@@ -87,6 +110,32 @@ impl Library {
     }
 }
 
+/// Look for the entry points of other versions of the library's ABI, to report which version was
+/// found instead of a missing symbol.
+fn unsupported_abi(library: &libloading::Library) -> Option<LibraryError> {
+    const OTHER_ENTRY_POINTS: &[&[u8]] = &[
+        b"cmajor_getEntryPointsV9",
+        b"cmajor_getEntryPointsV11",
+        b"cmajor_getEntryPointsV12",
+    ];
+
+    let entry_point_fn = OTHER_ENTRY_POINTS
+        .iter()
+        .find_map(|symbol| unsafe { library.get::<CMajorGetEntryPointsV10>(symbol) }.ok())?;
+
+    // Every version of the entry points starts with `get_version`, so it's the one function that
+    // can be called through a mismatched vtable.
+    let found_version = Library {
+        ptr: unsafe { entry_point_fn() }.cast(),
+        _library: None,
+    }
+    .version()
+    .to_string_lossy()
+    .into_owned();
+
+    Some(LibraryError::UnsupportedAbi { found_version })
+}
+
 #[repr(C)]
 struct EntryPointsVTable {
     get_version: unsafe extern "system" fn(*mut EntryPoints) -> *const c_char,
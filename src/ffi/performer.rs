@@ -96,6 +96,10 @@ impl PerformerPtr {
         unsafe { (self.vtable().add_input_event)(self.ptr, handle, type_index, data_ptr) };
     }
 
+    pub fn reset(&self) {
+        unsafe { (self.vtable().reset)(self.ptr) };
+    }
+
     pub fn advance(&self) {
         unsafe { (self.vtable().advance)(self.ptr) };
         check_for_panic();
@@ -180,6 +184,10 @@ impl PerformerPtr {
         unsafe { (self.vtable().get_max_block_size)(self.ptr) }
     }
 
+    pub fn get_event_buffer_size(&self) -> u32 {
+        unsafe { (self.vtable().get_event_buffer_size)(self.ptr) }
+    }
+
     pub fn get_latency(&self) -> f64 {
         unsafe { (self.vtable().get_latency)(self.ptr) }
     }
@@ -3,8 +3,9 @@
 //! Rust bindings for the Cmajor JIT engine.
 
 pub use {
-    library::{Cmajor, LibraryError},
-    program::{ParseError, Program},
+    cmajor_macros::include_cmajor,
+    library::{Cmajor, LibraryError, Version},
+    program::{ParseError, ParseOptions, Program},
     serde_json as json,
 };
 
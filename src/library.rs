@@ -1,11 +1,16 @@
 use {
     crate::{
+        diagnostic::Diagnostics,
         engine::{Engine, EngineBuilder, EngineType, EngineTypes},
         ffi::Library,
-        program::Program,
+        program::{ParseOptions, Program},
         ParseError,
     },
-    std::{ffi::CString, path::Path},
+    std::{
+        ffi::CString,
+        fmt::{self, Display, Formatter},
+        path::Path,
+    },
 };
 
 /// An error that can occur when loading the Cmajor library.
@@ -15,6 +20,13 @@ pub enum LibraryError {
     #[error("Failed to load library")]
     FailedToLoadLibrary(#[from] libloading::Error),
 
+    /// The library doesn't provide a version of the API supported by this crate.
+    #[error("Unsupported Cmajor library version: {found_version}")]
+    UnsupportedAbi {
+        /// The version reported by the library.
+        found_version: String,
+    },
+
     /// Failed to create an engine of the requested type.
     #[error("Engine not found")]
     EngineNotFound,
@@ -25,6 +37,10 @@ pub enum LibraryError {
 }
 
 /// The Cmajor library.
+///
+/// `Cmajor` is cheap to clone, with clones sharing the same loaded library. It is `Send` and
+/// `Sync`, so engines can be created from multiple threads using a single loaded library.
+#[derive(Clone)]
 pub struct Cmajor {
     library: Library,
 }
@@ -55,6 +71,9 @@ impl Cmajor {
     }
 
     /// Load the Cmajor library at the given path.
+    ///
+    /// Returns [`LibraryError::UnsupportedAbi`] if the library is a version of Cmajor with an
+    /// incompatible API.
     pub fn new_from_path(path_to_library: impl AsRef<Path>) -> Result<Self, LibraryError> {
         let library = Library::load(path_to_library)?;
         Ok(Self { library })
@@ -74,16 +93,44 @@ impl Cmajor {
         self.library.version().to_str().unwrap_or_default()
     }
 
+    /// Returns the parsed version of the Cmajor library, if it is in a recognised format.
+    pub fn version_parsed(&self) -> Option<Version> {
+        Version::parse(self.version())
+    }
+
     fn create_program(&self) -> Program {
         Program {
             inner: self.library.create_program(),
+            warnings: Diagnostics::default(),
         }
     }
 
     /// Parse a Cmajor program.
     pub fn parse(&self, cmajor_program: impl AsRef<str>) -> Result<Program, ParseError> {
+        self.parse_with_options(cmajor_program, ParseOptions::default())
+    }
+
+    /// Parse a Cmajor program with the given options.
+    pub fn parse_with_options(
+        &self,
+        cmajor_program: impl AsRef<str>,
+        options: ParseOptions,
+    ) -> Result<Program, ParseError> {
         let mut program = self.create_program();
-        program.parse(cmajor_program)?;
+        program.parse(None, cmajor_program, options)?;
+        Ok(program)
+    }
+
+    /// Parse a Cmajor program from a file.
+    ///
+    /// The file's path is reported as the file name in any diagnostics. Further files can be added
+    /// with [`Program::add_source`].
+    pub fn parse_file(&self, path: impl AsRef<Path>) -> Result<Program, ParseError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+
+        let mut program = self.create_program();
+        program.add_source(path.to_string_lossy(), source)?;
         Ok(program)
     }
 
@@ -92,25 +139,191 @@ impl Cmajor {
         EngineTypes::new(self.library.engine_types())
     }
 
+    /// Returns `true` if the library supports the engine type with the given name.
+    pub fn has_engine_type(&self, name: impl AsRef<str>) -> bool {
+        self.engine_types()
+            .any(|engine_type| engine_type == *name.as_ref())
+    }
+
     /// Create the default engine type (LLVM JIT).
+    ///
+    /// If the default engine isn't available, the first engine type the library supports is used
+    /// instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no engine type is available. Use [`Cmajor::try_create_default_engine`] to
+    /// handle this case.
     pub fn create_default_engine(&self) -> EngineBuilder {
-        self.create_engine(EngineType::default_engine_type())
+        self.try_create_default_engine()
+            .expect("no engine type available")
+    }
+
+    /// Create the default engine type (LLVM JIT), falling back to the other engine types the
+    /// library supports.
+    ///
+    /// Returns [`LibraryError::EngineNotFound`] if none of them can be created.
+    pub fn try_create_default_engine(&self) -> Result<EngineBuilder, LibraryError> {
+        self.try_create_engine(EngineType::default()).or_else(|_| {
+            self.engine_types()
+                .find_map(|engine_type| self.try_create_engine(engine_type).ok())
+                .ok_or(LibraryError::EngineNotFound)
+        })
     }
 
     /// Create a new engine of the given type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the engine type isn't supported. Use [`Cmajor::try_create_engine`] to handle
+    /// this case.
     pub fn create_engine(&self, engine_type: EngineType) -> EngineBuilder {
-        let engine_type = CString::new(engine_type.to_str())
-            .expect("engine type should not contain a null character");
+        self.try_create_engine(engine_type)
+            .expect("engine factory not found")
+    }
+
+    /// Create a new engine of the given type, returning [`LibraryError::EngineNotFound`] if the
+    /// engine type isn't supported.
+    pub fn try_create_engine(
+        &self,
+        engine_type: EngineType,
+    ) -> Result<EngineBuilder, LibraryError> {
+        let engine_type =
+            CString::new(engine_type.name()).map_err(|_| LibraryError::EngineNotFound)?;
 
         let engine_factory = self
             .library
             .create_engine_factory(engine_type.as_c_str())
-            .expect("engine factory not found");
+            .ok_or(LibraryError::EngineNotFound)?;
         let engine = engine_factory.create_engine(None);
 
-        EngineBuilder {
-            sample_rate: 0.0,
-            engine: Engine::new(engine),
+        Ok(EngineBuilder::new(Engine::new(engine)))
+    }
+}
+
+/// A version of the Cmajor library.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// The major version.
+    pub major: u32,
+
+    /// The minor version.
+    pub minor: u32,
+
+    /// The patch version.
+    pub patch: u32,
+
+    /// Anything following the patch version, e.g. a pre-release suffix.
+    pub pre: Option<String>,
+
+    /// Build metadata following a `+`, e.g. a commit hash.
+    pub build: Option<String>,
+}
+
+impl Version {
+    /// Parse a version string of the form `major.minor.patch`, optionally followed by a suffix
+    /// and `+build` metadata.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+
+        let (version, build) = match version.split_once('+') {
+            Some((version, build)) => (version, Some(build)),
+            None => (version, None),
+        };
+
+        let (numbers, pre) = match version.find(|c: char| !c.is_ascii_digit() && c != '.') {
+            Some(index) => (&version[..index], Some(&version[index..])),
+            None => (version, None),
+        };
+
+        let mut numbers = numbers.split('.').map(str::parse);
+        let major = numbers.next()?.ok()?;
+        let minor = numbers.next()?.ok()?;
+        let patch = numbers.next()?.ok()?;
+        if numbers.next().is_some() {
+            return None;
+        }
+
+        let pre = pre
+            .map(|pre| pre.trim_start_matches('-'))
+            .filter(|pre| !pre.is_empty())
+            .map(str::to_owned);
+        let build = build.filter(|build| !build.is_empty()).map(str::to_owned);
+
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{build}")?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cmajor_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Cmajor>();
+    }
+
+    #[test]
+    fn parse_version() {
+        assert_eq!(
+            Version::parse("1.0.2616"),
+            Some(Version {
+                major: 1,
+                minor: 0,
+                patch: 2616,
+                pre: None,
+                build: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_version_with_suffix() {
+        let version = Version::parse("1.2.3-beta.1").unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (1, 2, 3));
+        assert_eq!(version.pre.as_deref(), Some("beta.1"));
+        assert_eq!(version.to_string(), "1.2.3-beta.1");
+    }
+
+    #[test]
+    fn parse_version_with_build_metadata() {
+        let version = Version::parse("1.2.3+abc123").unwrap();
+        assert_eq!(version.pre, None);
+        assert_eq!(version.build.as_deref(), Some("abc123"));
+        assert_eq!(version.to_string(), "1.2.3+abc123");
+
+        let version = Version::parse("1.2.3-beta.1+abc123").unwrap();
+        assert_eq!(version.pre.as_deref(), Some("beta.1"));
+        assert_eq!(version.build.as_deref(), Some("abc123"));
+        assert_eq!(Version::parse(&version.to_string()), Some(version));
+    }
+
+    #[test]
+    fn parse_invalid_versions() {
+        assert_eq!(Version::parse(""), None);
+        assert_eq!(Version::parse("1.2"), None);
+        assert_eq!(Version::parse("1.2.3.4"), None);
+        assert_eq!(Version::parse("one.two.three"), None);
     }
 }
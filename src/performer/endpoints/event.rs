@@ -60,23 +60,38 @@ impl EndpointType for OutputEvent {
 
 pub fn post_event(
     performer: &mut Performer,
-    Endpoint(endpoint): Endpoint<InputEvent>,
+    endpoint: Endpoint<InputEvent>,
     event: ValueRef<'_>,
 ) -> Result<(), EndpointError> {
-    let type_index = performer
-        .endpoints
+    post_events(performer, endpoint, std::iter::once(event))
+}
+
+pub fn post_events<'a>(
+    performer: &mut Performer,
+    Endpoint(endpoint): Endpoint<InputEvent>,
+    events: impl IntoIterator<Item = ValueRef<'a>>,
+) -> Result<(), EndpointError> {
+    let Performer { ptr, endpoints, .. } = performer;
+
+    let endpoint_info = endpoints
         .get(&endpoint.handle)
         .ok_or(EndpointError::EndpointDoesNotExist)?
         .as_event()
-        .ok_or(EndpointError::EndpointTypeMismatch)?
-        .type_index(event.ty())
-        .ok_or(EndpointError::DataTypeMismatch)?;
+        .ok_or(EndpointError::EndpointTypeMismatch)?;
 
-    event.with_bytes(|bytes| {
-        performer
-            .ptr
-            .add_input_event(endpoint.handle, type_index, bytes);
-    });
+    for event in events {
+        if let ValueRef::Text(_) = event {
+            return Err(EndpointError::UnsupportedValue);
+        }
+
+        let type_index = endpoint_info
+            .type_index(event.ty())
+            .ok_or(EndpointError::DataTypeMismatch)?;
+
+        event.with_bytes(|bytes| {
+            ptr.add_input_event(endpoint.handle, type_index, bytes);
+        });
+    }
 
     Ok(())
 }
@@ -85,7 +100,7 @@ pub fn fetch_events(
     performer: &Performer,
     Endpoint(endpoint): Endpoint<OutputEvent>,
     mut callback: impl FnMut(usize, ValueRef<'_>),
-) -> Result<(), EndpointError> {
+) -> Result<usize, EndpointError> {
     let types = performer
         .endpoints
         .get(&endpoint.handle)
@@ -93,6 +108,7 @@ pub fn fetch_events(
         .map(|endpoint| endpoint.types())
         .expect("endpoint should exist and be an event endpoint");
 
+    let mut count = 0;
     performer
         .ptr
         .iterate_output_events(endpoint.handle, |frame_offset, _, type_index, data| {
@@ -101,8 +117,43 @@ pub fn fetch_events(
 
             if let Some(ty) = ty {
                 callback(frame_offset, ValueRef::new_from_slice(ty.as_ref(), data));
+                count += 1;
             }
         });
 
-    Ok(())
+    Ok(count)
+}
+
+pub fn fetch_all_events(
+    performer: &Performer,
+    mut callback: impl FnMut(Endpoint<OutputEvent>, usize, ValueRef<'_>),
+) -> usize {
+    let mut count = 0;
+    for (&handle, endpoint) in &performer.endpoints {
+        let Some(types) = endpoint
+            .as_event()
+            .filter(|_| endpoint.is_output())
+            .map(|endpoint| endpoint.types())
+        else {
+            continue;
+        };
+
+        performer
+            .ptr
+            .iterate_output_events(handle, |frame_offset, _, type_index, data| {
+                let ty = types.get(usize::from(type_index));
+                debug_assert!(ty.is_some(), "Invalid type index from Cmajor");
+
+                if let Some(ty) = ty {
+                    callback(
+                        Endpoint(OutputEvent { handle }),
+                        frame_offset,
+                        ValueRef::new_from_slice(ty.as_ref(), data),
+                    );
+                    count += 1;
+                }
+            });
+    }
+
+    count
 }
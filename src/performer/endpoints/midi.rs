@@ -0,0 +1,93 @@
+use crate::{
+    endpoint::EndpointTypeIndex,
+    performer::{
+        endpoints::event::fetch_events, Endpoint, EndpointError, EndpointType, InputEvent,
+        OutputEvent, Performer,
+    },
+    value::{
+        types::{Object, Type},
+        ValueRef,
+    },
+};
+
+/// Pack a MIDI message into the `int32` representation used by `std::midi::Message`.
+fn pack([status, data1, data2]: [u8; 3]) -> i32 {
+    (i32::from(status) << 16) | (i32::from(data1) << 8) | i32::from(data2)
+}
+
+/// Unpack a MIDI message from the `int32` representation used by `std::midi::Message`.
+fn unpack(message: i32) -> [u8; 3] {
+    [(message >> 16) as u8, (message >> 8) as u8, message as u8]
+}
+
+/// Whether the object has the layout of `std::midi::Message`.
+fn is_midi_message(object: &Object) -> bool {
+    let mut fields = object.fields();
+    matches!(
+        (fields.next(), fields.next()),
+        (Some(field), None) if field.name() == "message" && field.ty() == &Type::Int32
+    )
+}
+
+pub fn post_midi(
+    performer: &mut Performer,
+    Endpoint(endpoint): Endpoint<InputEvent>,
+    message: [u8; 3],
+) -> Result<(), EndpointError> {
+    let Performer { ptr, endpoints, .. } = performer;
+
+    // A `std::midi::Message` has the same layout as its packed `int32`, so either type can be
+    // posted without building a value.
+    let type_index = endpoints
+        .get(&endpoint.handle())
+        .ok_or(EndpointError::EndpointDoesNotExist)?
+        .as_event()
+        .ok_or(EndpointError::EndpointTypeMismatch)?
+        .types()
+        .iter()
+        .position(|ty| match ty {
+            Type::Object(object) => is_midi_message(object),
+            Type::Int32 => true,
+            _ => false,
+        })
+        .map(EndpointTypeIndex::from)
+        .ok_or(EndpointError::DataTypeMismatch)?;
+
+    ptr.add_input_event(endpoint.handle(), type_index, &pack(message).to_ne_bytes());
+    Ok(())
+}
+
+pub fn fetch_midi(
+    performer: &Performer,
+    endpoint: Endpoint<OutputEvent>,
+    mut callback: impl FnMut(usize, [u8; 3]),
+) -> Result<usize, EndpointError> {
+    let mut count = 0;
+    fetch_events(performer, endpoint, |frame_offset, value| {
+        let message = match value {
+            ValueRef::Int32(message) => Some(message),
+            ValueRef::Object(object) => match object.field("message") {
+                Some(ValueRef::Int32(message)) => Some(message),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(message) = message {
+            callback(frame_offset, unpack(message));
+            count += 1;
+        }
+    })?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn midi_messages_are_packed_into_an_int() {
+        assert_eq!(pack([0x90, 60, 127]), 0x903C7F);
+        assert_eq!(unpack(0x903C7F), [0x90, 60, 127]);
+    }
+}
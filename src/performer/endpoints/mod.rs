@@ -1,7 +1,23 @@
+use crate::{endpoint::EndpointHandle, performer::EndpointType};
+
 pub mod event;
+pub mod midi;
 pub mod stream;
 pub mod value;
 
 /// An endpoint.
 #[derive(Debug, Copy, Clone)]
 pub struct Endpoint<T>(pub(crate) T);
+
+impl<T> Endpoint<T>
+where
+    T: EndpointType,
+{
+    /// Returns the handle of the endpoint.
+    ///
+    /// Handles can be converted to and from a `u32` for persistence, and turned back into an
+    /// endpoint with [`Engine::endpoint_for_handle`](crate::engine::Engine::endpoint_for_handle).
+    pub fn handle(&self) -> EndpointHandle {
+        self.0.handle()
+    }
+}
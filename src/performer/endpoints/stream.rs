@@ -4,7 +4,7 @@ use {
         performer::{Endpoint, EndpointError, EndpointType, Performer},
         value::types::{IsScalar, Type},
     },
-    std::marker::PhantomData,
+    std::{marker::PhantomData, mem},
 };
 
 /// An input stream.
@@ -122,6 +122,84 @@ pub fn read_stream<T>(
     }
 }
 
+pub fn write_planar_stream<T, const N: usize>(
+    performer: &mut Performer,
+    Endpoint(endpoint): Endpoint<InputStream<[T; N]>>,
+    channels: &[&[T]],
+) -> Result<(), EndpointError>
+where
+    T: StreamType + IsScalar,
+{
+    let num_frames = validate_channels::<N>(channels.iter().map(|channel| channel.len()))?;
+
+    let frames = scratch_frames::<[T; N]>(&mut performer.scratch, num_frames);
+    for (index, frame) in frames.iter_mut().enumerate() {
+        for (sample, channel) in frame.iter_mut().zip(channels) {
+            *sample = channel[index];
+        }
+    }
+
+    unsafe { performer.ptr.set_input_frames(endpoint.handle, frames) };
+
+    Ok(())
+}
+
+pub fn read_planar_stream<T, const N: usize>(
+    performer: &mut Performer,
+    Endpoint(endpoint): Endpoint<OutputStream<[T; N]>>,
+    channels: &mut [&mut [T]],
+) -> Result<(), EndpointError>
+where
+    T: StreamType + IsScalar,
+{
+    let num_frames = validate_channels::<N>(channels.iter().map(|channel| channel.len()))?;
+
+    let frames = scratch_frames::<[T; N]>(&mut performer.scratch, num_frames);
+    unsafe { performer.ptr.copy_output_frames(endpoint.handle, frames) };
+
+    for (index, frame) in frames.iter().enumerate() {
+        for (sample, channel) in frame.iter().zip(channels.iter_mut()) {
+            channel[index] = *sample;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_channels<const N: usize>(
+    mut lengths: impl ExactSizeIterator<Item = usize>,
+) -> Result<usize, EndpointError> {
+    if lengths.len() != N {
+        return Err(EndpointError::ChannelCountMismatch);
+    }
+
+    let num_frames = lengths.next().unwrap_or(0);
+    if lengths.any(|len| len != num_frames) {
+        return Err(EndpointError::ChannelLengthMismatch);
+    }
+
+    Ok(num_frames)
+}
+
+/// Borrow the performer's scratch space as a slice of zeroed frames, growing it if needed.
+fn scratch_frames<F>(scratch: &mut Vec<u64>, num_frames: usize) -> &mut [F]
+where
+    F: StreamType,
+{
+    debug_assert!(mem::align_of::<F>() <= mem::align_of::<u64>());
+
+    let num_bytes = num_frames * mem::size_of::<F>();
+    let num_words = num_bytes.div_ceil(mem::size_of::<u64>());
+    if scratch.len() < num_words {
+        scratch.resize(num_words, 0);
+    }
+    scratch[..num_words].fill(0);
+
+    // SAFETY: stream types are plain scalars (or arrays of them) for which all-zero bytes are
+    // valid, and the scratch buffer is large enough and suitably aligned.
+    unsafe { std::slice::from_raw_parts_mut(scratch.as_mut_ptr().cast(), num_frames) }
+}
+
 pub trait StreamType: Copy + sealed::Sealed {
     type Element: IsScalar + 'static;
     const EXTENT: usize;
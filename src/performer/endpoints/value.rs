@@ -1,10 +1,21 @@
 use {
     crate::{
         endpoint::{EndpointDirection, EndpointHandle, EndpointInfo},
+        ffi::PerformerPtr,
         performer::{endpoints::Endpoint, EndpointError, EndpointType, Performer},
-        value::{Value, ValueRef},
+        value::{
+            types::{IsPrimitive, Type},
+            Value, ValueRef,
+        },
+    },
+    std::{
+        marker::PhantomData,
+        ops::Range,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
     },
-    std::{any::TypeId, marker::PhantomData},
 };
 
 /// An endpoint for input values.
@@ -23,7 +34,7 @@ pub struct OutputValue<T = Value> {
 
 impl<T> EndpointType for InputValue<T>
 where
-    T: 'static,
+    T: ValueEndpointType,
 {
     fn make(
         handle: EndpointHandle,
@@ -44,7 +55,7 @@ where
 
 impl<T> EndpointType for OutputValue<T>
 where
-    T: 'static,
+    T: ValueEndpointType,
 {
     fn make(
         handle: EndpointHandle,
@@ -68,7 +79,7 @@ fn validate_value_endpoint<T>(
     expected_direction: EndpointDirection,
 ) -> Result<(), EndpointError>
 where
-    T: 'static,
+    T: ValueEndpointType,
 {
     if endpoint.direction() != expected_direction {
         return Err(EndpointError::DirectionMismatch);
@@ -78,20 +89,69 @@ where
         .as_value()
         .ok_or(EndpointError::EndpointTypeMismatch)?;
 
-    let user_type = TypeId::of::<T>();
-    if user_type != TypeId::of::<Value>() {
-        if let Some(endpoint_type) = endpoint.ty().type_id() {
-            if user_type != endpoint_type {
-                return Err(EndpointError::DataTypeMismatch);
-            }
-        } else {
-            return Err(EndpointError::DataTypeMismatch);
-        }
+    if !T::matches(endpoint.ty()) {
+        return Err(EndpointError::DataTypeMismatch);
     }
 
     Ok(())
 }
 
+fn debug_assert_endpoint_type<T>(performer: &Performer, handle: EndpointHandle)
+where
+    T: ValueEndpointType,
+{
+    debug_assert!(
+        performer
+            .endpoints
+            .get(&handle)
+            .and_then(|endpoint| endpoint.as_value())
+            .is_some_and(|endpoint| T::matches(endpoint.ty())),
+        "endpoint type does not match the value being written"
+    );
+}
+
+/// A type that can be read from or written to a value endpoint.
+#[doc(hidden)]
+pub trait ValueEndpointType: 'static {
+    fn matches(ty: &Type) -> bool;
+}
+
+impl ValueEndpointType for Value {
+    fn matches(_: &Type) -> bool {
+        true
+    }
+}
+
+macro_rules! value_endpoint_type_for {
+    ($ty:ty) => {
+        impl ValueEndpointType for $ty {
+            fn matches(ty: &Type) -> bool {
+                ty.as_primitive() == Some(<$ty as IsPrimitive>::PRIMITIVE)
+            }
+        }
+    };
+}
+
+value_endpoint_type_for! {bool}
+value_endpoint_type_for! {i32}
+value_endpoint_type_for! {i64}
+value_endpoint_type_for! {f32}
+value_endpoint_type_for! {f64}
+
+impl<T, const N: usize> ValueEndpointType for [T; N]
+where
+    T: IsPrimitive + 'static,
+{
+    fn matches(ty: &Type) -> bool {
+        match ty {
+            Type::Array(array) => {
+                array.len() == N && array.elem_ty().as_primitive() == Some(T::PRIMITIVE)
+            }
+            _ => false,
+        }
+    }
+}
+
 #[doc(hidden)]
 pub trait SetInputValue: Sized {
     type Output;
@@ -100,6 +160,7 @@ pub trait SetInputValue: Sized {
         performer: &mut Performer,
         endpoint: Endpoint<InputValue<Self>>,
         value: Self,
+        num_frames_to_reach_value: u32,
     ) -> Self::Output;
 }
 
@@ -112,12 +173,15 @@ macro_rules! set_input_value_for {
                 performer: &mut Performer,
                 Endpoint(endpoint): Endpoint<InputValue<Self>>,
                 value: Self,
+                num_frames_to_reach_value: u32,
             ) -> Self::Output {
-                unsafe {
-                    performer
-                        .ptr
-                        .set_input_value(endpoint.handle, value.to_ne_bytes().as_ptr(), 0);
-                }
+                debug_assert_endpoint_type::<Self>(performer, endpoint.handle);
+                write_input_value(
+                    performer,
+                    endpoint.handle,
+                    &value.to_ne_bytes(),
+                    num_frames_to_reach_value,
+                );
             }
         }
     };
@@ -135,13 +199,50 @@ impl SetInputValue for bool {
         performer: &mut Performer,
         Endpoint(endpoint): Endpoint<InputValue<Self>>,
         value: Self,
+        num_frames_to_reach_value: u32,
     ) -> Self::Output {
+        debug_assert_endpoint_type::<Self>(performer, endpoint.handle);
         let value: i32 = if value { 1 } else { 0 };
-        unsafe {
-            performer
-                .ptr
-                .set_input_value(endpoint.handle, value.to_ne_bytes().as_ptr(), 0);
+        write_input_value(
+            performer,
+            endpoint.handle,
+            &value.to_ne_bytes(),
+            num_frames_to_reach_value,
+        );
+    }
+}
+
+impl<T, const N: usize> SetInputValue for [T; N]
+where
+    T: IsPrimitive + Into<ValueRef<'static>> + 'static,
+{
+    type Output = ();
+
+    fn set_input_value(
+        performer: &mut Performer,
+        Endpoint(endpoint): Endpoint<InputValue<Self>>,
+        value: Self,
+        num_frames_to_reach_value: u32,
+    ) -> Self::Output {
+        debug_assert_endpoint_type::<Self>(performer, endpoint.handle);
+
+        let mut buffer = std::mem::take(&mut performer.buffer);
+
+        let mut len = 0;
+        for elem in value {
+            elem.into().with_bytes(|bytes| {
+                buffer[len..len + bytes.len()].copy_from_slice(bytes);
+                len += bytes.len();
+            });
         }
+
+        write_input_value(
+            performer,
+            endpoint.handle,
+            &buffer[..len],
+            num_frames_to_reach_value,
+        );
+        performer.buffer = buffer;
     }
 }
 
@@ -152,6 +253,7 @@ impl SetInputValue for Value {
         performer: &mut Performer,
         Endpoint(endpoint): Endpoint<InputValue<Self>>,
         value: Self,
+        num_frames_to_reach_value: u32,
     ) -> Self::Output {
         let ty = performer
             .endpoints
@@ -161,20 +263,83 @@ impl SetInputValue for Value {
             .ok_or(EndpointError::EndpointTypeMismatch)?
             .ty();
 
-        if ty.as_ref() != value.ty() {
+        if !value_matches_type(&value, ty, performer.ignore_class_names) {
             return Err(EndpointError::DataTypeMismatch);
         }
 
-        value.with_bytes(|bytes| unsafe {
-            performer
-                .ptr
-                .set_input_value(endpoint.handle, bytes.as_ptr(), 0);
+        if let Value::Text(_) = value {
+            return Err(EndpointError::UnsupportedValue);
+        }
+
+        value.with_bytes(|bytes| {
+            write_input_value(performer, endpoint.handle, bytes, num_frames_to_reach_value)
         });
 
         Ok(())
     }
 }
 
+/// Check that a value can be written to a value endpoint of the given type.
+///
+/// If `ignore_class_names` is set, objects only need the same fields as the endpoint's type, in the
+/// same order (see [`Type::compatible_with`]).
+pub(crate) fn value_matches_type(value: &Value, ty: &Type, ignore_class_names: bool) -> bool {
+    let matches = if ignore_class_names {
+        ty.as_ref().structurally_eq(value.ty())
+    } else {
+        ty.as_ref() == value.ty()
+    };
+
+    matches && ty.size() == value.ty().size()
+}
+
+/// Write an input value, or stage it if the performer is buffering writes.
+fn write_input_value(
+    performer: &mut Performer,
+    handle: EndpointHandle,
+    bytes: &[u8],
+    num_frames_to_reach_value: u32,
+) {
+    match &mut performer.staged {
+        Some(staged) => staged.push(handle, bytes, num_frames_to_reach_value),
+        None => unsafe {
+            performer
+                .ptr
+                .set_input_value(handle, bytes.as_ptr(), num_frames_to_reach_value);
+        },
+    }
+}
+
+/// Input values staged by a [`Performer`] in buffered mode.
+#[derive(Debug, Default)]
+pub(crate) struct StagedValues {
+    writes: Vec<(EndpointHandle, Range<usize>, u32)>,
+    bytes: Vec<u8>,
+}
+
+impl StagedValues {
+    fn push(&mut self, handle: EndpointHandle, bytes: &[u8], num_frames_to_reach_value: u32) {
+        let start = self.bytes.len();
+        self.bytes.extend_from_slice(bytes);
+        self.writes
+            .push((handle, start..self.bytes.len(), num_frames_to_reach_value));
+    }
+
+    /// Apply the staged values in the order they were set, then clear them.
+    pub(crate) fn apply(&mut self, ptr: &PerformerPtr) {
+        for (handle, range, num_frames_to_reach_value) in self.writes.drain(..) {
+            unsafe {
+                ptr.set_input_value(
+                    handle,
+                    self.bytes[range].as_ptr(),
+                    num_frames_to_reach_value,
+                );
+            }
+        }
+        self.bytes.clear();
+    }
+}
+
 #[doc(hidden)]
 pub trait GetOutputValue: Sized {
     type Output<'a>;
@@ -224,6 +389,31 @@ impl GetOutputValue for bool {
     }
 }
 
+impl<T, const N: usize> GetOutputValue for [T; N]
+where
+    T: IsPrimitive + for<'a> TryFrom<ValueRef<'a>> + 'static,
+{
+    type Output<'a> = Self;
+
+    fn get_output_value(
+        performer: &mut Performer,
+        Endpoint(endpoint): Endpoint<OutputValue<Self>>,
+    ) -> Self::Output<'_> {
+        let Performer { ptr, buffer, .. } = performer;
+
+        ptr.copy_output_value(endpoint.handle, buffer);
+
+        let elem_ty = Type::from(T::PRIMITIVE);
+        let elem_size = elem_ty.size();
+        std::array::from_fn(|index| {
+            let elem = ValueRef::new_from_slice(elem_ty.as_ref(), &buffer[index * elem_size..]);
+            T::try_from(elem)
+                .ok()
+                .expect("endpoint type is checked when the endpoint is created")
+        })
+    }
+}
+
 impl GetOutputValue for Value {
     type Output<'a> = Result<ValueRef<'a>, ()>;
 
@@ -245,3 +435,155 @@ impl GetOutputValue for Value {
         Ok(ValueRef::new_from_slice(ty, &buffer[..ty.size()]))
     }
 }
+
+/// A reader for a primitive output value that can be shared with other threads.
+///
+/// Created with [`Performer::output_value_reader`]. The value is a snapshot taken each time the
+/// performer advances, so it can be read without access to the performer.
+#[derive(Debug, Clone)]
+pub struct OutputValueReader<T> {
+    bits: Arc<AtomicU64>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> OutputValueReader<T>
+where
+    T: AtomicOutputValue,
+{
+    /// Get the value of the endpoint as of the last block the performer rendered.
+    pub fn get(&self) -> T {
+        T::from_snapshot(self.bits.load(Ordering::Acquire))
+    }
+}
+
+/// The latest value of an output endpoint, shared with its [`OutputValueReader`]s.
+pub(crate) struct OutputValueSnapshot {
+    handle: EndpointHandle,
+    bits: Arc<AtomicU64>,
+}
+
+impl OutputValueSnapshot {
+    pub(crate) fn update(&self, ptr: &PerformerPtr) {
+        let mut buffer = [0u8; size_of::<u64>()];
+        ptr.copy_output_value(self.handle, &mut buffer);
+        self.bits
+            .store(u64::from_ne_bytes(buffer), Ordering::Release);
+    }
+}
+
+#[doc(hidden)]
+pub trait AtomicOutputValue: GetOutputValue + Copy + 'static {
+    fn from_snapshot(bits: u64) -> Self;
+}
+
+macro_rules! atomic_output_value_for {
+    ($ty:ty) => {
+        impl AtomicOutputValue for $ty {
+            fn from_snapshot(bits: u64) -> Self {
+                let mut bytes = [0u8; size_of::<Self>()];
+                bytes.copy_from_slice(&bits.to_ne_bytes()[..size_of::<Self>()]);
+                Self::from_ne_bytes(bytes)
+            }
+        }
+    };
+}
+
+atomic_output_value_for! {i32}
+atomic_output_value_for! {i64}
+atomic_output_value_for! {f32}
+atomic_output_value_for! {f64}
+
+impl AtomicOutputValue for bool {
+    fn from_snapshot(bits: u64) -> Self {
+        i32::from_snapshot(bits) != 0
+    }
+}
+
+pub fn output_value_reader<T>(
+    performer: &mut Performer,
+    Endpoint(endpoint): Endpoint<OutputValue<T>>,
+) -> OutputValueReader<T>
+where
+    T: AtomicOutputValue,
+{
+    let existing = performer
+        .snapshots
+        .iter()
+        .find(|snapshot| snapshot.handle == endpoint.handle);
+
+    let bits = match existing {
+        Some(snapshot) => Arc::clone(&snapshot.bits),
+        None => {
+            let snapshot = OutputValueSnapshot {
+                handle: endpoint.handle,
+                bits: Arc::default(),
+            };
+            snapshot.update(&performer.ptr);
+
+            let bits = Arc::clone(&snapshot.bits);
+            performer.snapshots.push(snapshot);
+            bits
+        }
+    };
+
+    OutputValueReader {
+        bits,
+        _marker: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::value::{types::Object, ObjectValue},
+    };
+
+    #[test]
+    fn snapshots_decode_primitive_values() {
+        let bits = |bytes: &[u8]| {
+            let mut buffer = [0u8; 8];
+            buffer[..bytes.len()].copy_from_slice(bytes);
+            u64::from_ne_bytes(buffer)
+        };
+
+        assert_eq!(i32::from_snapshot(bits(&(-7_i32).to_ne_bytes())), -7);
+        assert_eq!(f32::from_snapshot(bits(&0.5_f32.to_ne_bytes())), 0.5);
+        assert_eq!(f64::from_snapshot(bits(&1.25_f64.to_ne_bytes())), 1.25);
+        assert!(bool::from_snapshot(bits(&1_u32.to_ne_bytes())));
+        assert!(!bool::from_snapshot(0));
+    }
+
+    #[test]
+    fn ignoring_class_names_still_checks_field_names() {
+        let ty = Type::from(
+            Object::new("Params")
+                .with_field("gain", Type::Float32)
+                .with_field("pan", Type::Float32),
+        );
+        let value = |class, first, second| -> Value {
+            ObjectValue::builder(class)
+                .field(first, 0.5_f32)
+                .field(second, 0.25_f32)
+                .build()
+                .unwrap()
+                .into()
+        };
+
+        assert!(value_matches_type(
+            &value("Other", "gain", "pan"),
+            &ty,
+            true
+        ));
+        assert!(!value_matches_type(
+            &value("Other", "gain", "pan"),
+            &ty,
+            false
+        ));
+        assert!(!value_matches_type(
+            &value("Params", "pan", "gain"),
+            &ty,
+            true
+        ));
+    }
+}
@@ -0,0 +1,124 @@
+use {
+    crate::{
+        endpoint::{EndpointHandle, EndpointInfo},
+        performer::{
+            endpoints::value::{value_matches_type, ValueEndpointType},
+            spsc::{EndpointMessage, Sender},
+            Endpoint, EndpointError, EndpointType, InputEvent, InputValue,
+        },
+        value::Value,
+    },
+    std::collections::HashMap,
+};
+
+/// A handle for updating a [`Performer`](super::Performer)'s inputs from another thread.
+///
+/// Updates are queued without locking or blocking, and are applied at the start of the next call
+/// to [`Performer::advance`](super::Performer::advance). If the queue is full, updates are
+/// rejected with [`EndpointError::QueueFull`] rather than dropped.
+pub struct PerformerHandle {
+    sender: Sender,
+    endpoints: HashMap<EndpointHandle, EndpointInfo>,
+    ignore_class_names: bool,
+}
+
+impl PerformerHandle {
+    pub(crate) fn new(
+        sender: Sender,
+        endpoints: HashMap<EndpointHandle, EndpointInfo>,
+        ignore_class_names: bool,
+    ) -> Self {
+        Self {
+            sender,
+            endpoints,
+            ignore_class_names,
+        }
+    }
+
+    /// Queue a value to be written to an input value endpoint.
+    ///
+    /// Values are checked against the endpoint's type in the same way as [`Performer::set`],
+    /// including whether object class names are ignored, as set on the performer before it was
+    /// split.
+    ///
+    /// [`Performer::set`]: super::Performer::set
+    pub fn write_value<T>(
+        &mut self,
+        Endpoint(endpoint): Endpoint<InputValue<T>>,
+        value: T,
+    ) -> Result<(), EndpointError>
+    where
+        T: Into<Value> + ValueEndpointType,
+    {
+        let handle = endpoint.handle();
+        let value = value.into();
+
+        let ty = self
+            .endpoints
+            .get(&handle)
+            .ok_or(EndpointError::EndpointDoesNotExist)?
+            .as_value()
+            .ok_or(EndpointError::EndpointTypeMismatch)?
+            .ty();
+
+        if !value_matches_type(&value, ty, self.ignore_class_names) {
+            return Err(EndpointError::DataTypeMismatch);
+        }
+
+        if let Value::Text(_) = value {
+            return Err(EndpointError::UnsupportedValue);
+        }
+
+        self.send(EndpointMessage::Value { handle, value })
+    }
+
+    /// Queue an event to be posted to an input event endpoint.
+    pub fn post_event(
+        &mut self,
+        Endpoint(endpoint): Endpoint<InputEvent>,
+        event: impl Into<Value>,
+    ) -> Result<(), EndpointError> {
+        let handle = endpoint.handle();
+        let value = event.into();
+
+        if let Value::Text(_) = value {
+            return Err(EndpointError::UnsupportedValue);
+        }
+
+        let type_index = self
+            .endpoints
+            .get(&handle)
+            .ok_or(EndpointError::EndpointDoesNotExist)?
+            .as_event()
+            .ok_or(EndpointError::EndpointTypeMismatch)?
+            .type_index(value.ty())
+            .ok_or(EndpointError::DataTypeMismatch)?;
+
+        self.send(EndpointMessage::Event {
+            handle,
+            type_index,
+            value,
+        })
+    }
+
+    /// The number of queued updates that the performer hasn't applied yet.
+    pub fn len(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Whether there are no queued updates waiting to be applied.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of updates that can be queued before [`EndpointError::QueueFull`] is returned.
+    pub fn remaining_capacity(&self) -> usize {
+        self.sender.remaining_capacity()
+    }
+
+    fn send(&mut self, message: EndpointMessage) -> Result<(), EndpointError> {
+        self.sender
+            .send(message)
+            .map_err(|_| EndpointError::QueueFull)
+    }
+}
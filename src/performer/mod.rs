@@ -1,33 +1,77 @@
 //! The Cmajor performer for running programs.
 
 mod endpoints;
+mod handle;
+mod spsc;
+#[cfg(feature = "audio-file")]
+pub mod wav;
 
-pub use endpoints::{
-    event::{InputEvent, OutputEvent},
-    stream::{InputStream, OutputStream},
-    value::{InputValue, OutputValue},
-    Endpoint,
-};
 use {
     crate::{
-        endpoint::{EndpointHandle, EndpointInfo},
-        ffi::PerformerPtr,
-        performer::endpoints::{
-            event::{fetch_events, post_event},
-            stream::{read_stream, write_stream, StreamType},
-            value::{GetOutputValue, SetInputValue},
+        endpoint::{EndpointDirection, EndpointHandle, EndpointInfo},
+        engine::DebugSink,
+        ffi::{with_debug_sink, PerformerPtr},
+        performer::{
+            endpoints::{
+                event::{fetch_all_events, fetch_events, post_event, post_events},
+                midi::{fetch_midi, post_midi},
+                stream::{
+                    read_planar_stream, read_stream, write_planar_stream, write_stream, StreamType,
+                },
+                value::{
+                    output_value_reader, AtomicOutputValue, GetOutputValue, OutputValueSnapshot,
+                    SetInputValue, StagedValues, ValueEndpointType,
+                },
+            },
+            spsc::EndpointMessage,
+        },
+        value::{
+            types::{IsScalar, Type},
+            AlignedBytes, StringHandle, Value, ValueRef,
         },
-        value::{StringHandle, ValueRef},
     },
     std::collections::HashMap,
 };
+pub use {
+    endpoints::{
+        event::{InputEvent, OutputEvent},
+        stream::{InputStream, OutputStream},
+        value::{InputValue, OutputValue, OutputValueReader},
+        Endpoint,
+    },
+    handle::PerformerHandle,
+};
 
 /// A Cmajor performer.
 pub struct Performer {
     ptr: PerformerPtr,
     endpoints: HashMap<EndpointHandle, EndpointInfo>,
-    buffer: Vec<u8>,
+    buffer: AlignedBytes,
+    scratch: Vec<u64>,
     console: Option<Endpoint<OutputEvent>>,
+    console_handler: Option<ConsoleHandler>,
+    console_delivered: bool,
+    messages: Option<spsc::Receiver>,
+    xrun_baseline: usize,
+    block_size: Option<u32>,
+    debug_sink: Option<DebugSink>,
+    sample_rate: f64,
+    snapshots: Vec<OutputValueSnapshot>,
+    staged: Option<StagedValues>,
+    ignore_class_names: bool,
+}
+
+/// The default number of pending updates a [`PerformerHandle`] can queue.
+const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+type ConsoleHandler = Box<dyn FnMut(ValueRef<'_>) + Send>;
+
+fn print_to_stdout(value: ValueRef<'_>) {
+    match value {
+        ValueRef::Text(text) => println!("{text}"),
+        ValueRef::String(_) => println!("?"),
+        value => println!("{value:?}"),
+    }
 }
 
 impl Performer {
@@ -35,6 +79,8 @@ impl Performer {
         performer: PerformerPtr,
         endpoints: HashMap<EndpointHandle, EndpointInfo>,
         console: Option<Endpoint<OutputEvent>>,
+        debug_sink: Option<DebugSink>,
+        sample_rate: f64,
     ) -> Self {
         let size_of_largest_type = endpoints
             .values()
@@ -45,8 +91,19 @@ impl Performer {
         Performer {
             ptr: performer,
             endpoints,
-            buffer: vec![0; size_of_largest_type],
+            buffer: AlignedBytes::zeroed(size_of_largest_type),
+            scratch: Vec::new(),
             console,
+            console_handler: Some(Box::new(print_to_stdout)),
+            console_delivered: false,
+            messages: None,
+            xrun_baseline: 0,
+            block_size: None,
+            debug_sink,
+            sample_rate,
+            snapshots: Vec::new(),
+            staged: None,
+            ignore_class_names: false,
         }
     }
 }
@@ -55,20 +112,199 @@ impl Performer {
     /// Sets the block size of the performer.
     pub fn set_block_size(&mut self, num_frames: u32) {
         self.ptr.set_block_size(num_frames);
+        self.block_size = Some(num_frames);
+    }
+
+    /// Returns the block size of the performer.
+    ///
+    /// This is the size set with [`Performer::set_block_size`], or the maximum block size if it
+    /// hasn't been set.
+    pub fn block_size(&self) -> u32 {
+        self.block_size
+            .unwrap_or_else(|| self.ptr.get_max_block_size())
+    }
+
+    /// Renders the next `num_frames` frames, returning the number of frames rendered.
+    ///
+    /// The number of frames is clamped to the maximum block size, so callers with larger blocks
+    /// should loop until all their frames have been rendered. The block size is only updated
+    /// when it differs from the last one used, so hosts with variable block sizes can call this
+    /// every block.
+    pub fn advance_frames(&mut self, num_frames: u32) -> u32 {
+        let num_frames = num_frames.min(self.get_max_block_size());
+        if self.block_size != Some(num_frames) {
+            self.set_block_size(num_frames);
+        }
+        self.advance();
+        num_frames
+    }
+
+    /// Render `num_frames` frames offline, in blocks of up to the maximum block size.
+    ///
+    /// For each block, the matching range of each input buffer is written to its stream, the
+    /// performer is advanced, and the output streams are read into the matching range of each
+    /// output buffer. Returns [`EndpointError::BufferLengthMismatch`] if any buffer is shorter
+    /// than `num_frames`.
+    pub fn render(
+        &mut self,
+        inputs: &[(Endpoint<InputStream<f32>>, &[f32])],
+        outputs: &mut [(Endpoint<OutputStream<f32>>, &mut [f32])],
+        num_frames: usize,
+    ) -> Result<(), EndpointError> {
+        let lengths = inputs
+            .iter()
+            .map(|(_, buffer)| buffer.len())
+            .chain(outputs.iter().map(|(_, buffer)| buffer.len()));
+        for len in lengths {
+            if len < num_frames {
+                return Err(EndpointError::BufferLengthMismatch {
+                    expected: num_frames,
+                    found: len,
+                });
+            }
+        }
+
+        let max_block_size = self.get_max_block_size().max(1) as usize;
+
+        let mut start = 0;
+        while start < num_frames {
+            let end = num_frames.min(start + max_block_size);
+
+            for (endpoint, buffer) in inputs {
+                write_stream(self, *endpoint, &buffer[start..end]);
+            }
+
+            self.advance_frames((end - start) as u32);
+
+            for (endpoint, buffer) in outputs.iter_mut() {
+                read_stream(self, *endpoint, &mut buffer[start..end]);
+            }
+
+            start = end;
+        }
+
+        Ok(())
+    }
+
+    /// Split the performer into itself and a [`PerformerHandle`] that can update its inputs from
+    /// another thread.
+    pub fn split(self) -> (Self, PerformerHandle) {
+        self.split_with_capacity(DEFAULT_QUEUE_CAPACITY)
+    }
+
+    /// Like [`Performer::split`], but with the given capacity for pending updates.
+    pub fn split_with_capacity(mut self, capacity: usize) -> (Self, PerformerHandle) {
+        let (sender, receiver) = spsc::channel(capacity);
+        self.messages = Some(receiver);
+
+        let handle = PerformerHandle::new(sender, self.endpoints.clone(), self.ignore_class_names);
+        (self, handle)
+    }
+
+    /// Enables or disables buffering of input values.
+    ///
+    /// While buffering, values set with [`Performer::set`] and [`Performer::set_ramped`] are
+    /// staged and applied together at the start of the next [`Performer::advance`], in the order
+    /// they were set, just like updates from a [`PerformerHandle`]. Disabling buffering applies
+    /// any staged values immediately.
+    pub fn set_buffered(&mut self, buffered: bool) {
+        match (buffered, self.staged.take()) {
+            (true, staged) => self.staged = Some(staged.unwrap_or_default()),
+            (false, Some(mut staged)) => staged.apply(&self.ptr),
+            (false, None) => {}
+        }
+    }
+
+    /// Returns `true` if input values are being buffered until the next advance.
+    pub fn is_buffered(&self) -> bool {
+        self.staged.is_some()
     }
 
     /// Renders the next block of frames.
+    ///
+    /// Any updates queued by a [`PerformerHandle`] are applied first, followed by any values
+    /// staged while buffering.
     pub fn advance(&mut self) {
-        self.ptr.advance();
+        self.apply_queued_messages();
+        if let Some(staged) = &mut self.staged {
+            staged.apply(&self.ptr);
+        }
+        with_debug_sink(self.debug_sink.as_ref(), || self.ptr.advance());
 
-        if let Some(console) = self.console {
+        for snapshot in &self.snapshots {
+            snapshot.update(&self.ptr);
+        }
+
+        self.console_delivered = false;
+        self.flush_console();
+    }
+
+    /// Passes the console output from the last rendered block to the console handler, without
+    /// advancing.
+    ///
+    /// Each block's output is passed to a handler at most once. [`Performer::advance`] already
+    /// does this after each block, so this does nothing unless the handler has been replaced
+    /// since, in which case the new handler receives the last block's output. This is useful for
+    /// handing the final block's output to a handler installed once rendering has stopped.
+    pub fn flush_console(&mut self) {
+        if self.console_delivered {
+            return;
+        }
+
+        if let (Some(console), Some(mut handler)) = (self.console, self.console_handler.take()) {
             let _ = fetch_events(self, console, |_, value| match value {
                 ValueRef::String(StringHandle(handle)) => {
-                    println!("{}", self.ptr.get_string_for_handle(handle).unwrap_or("?"));
+                    match self.ptr.get_string_for_handle(handle) {
+                        Some(text) => handler(ValueRef::Text(text)),
+                        None => handler(value),
+                    }
                 }
-                value => println!("{value:?}"),
+                value => handler(value),
             });
+            self.console_handler = Some(handler);
         }
+        self.console_delivered = true;
+    }
+
+    /// Renders the next block of frames, returning `true` if an xrun occurred while doing so.
+    pub fn advance_checked(&mut self) -> bool {
+        let xruns = self.ptr.get_xruns();
+        self.advance();
+        self.ptr.get_xruns() > xruns
+    }
+
+    fn apply_queued_messages(&mut self) {
+        let Some(messages) = &mut self.messages else {
+            return;
+        };
+
+        messages.for_each(|message| match message {
+            EndpointMessage::Value { handle, value } => value.with_bytes(|bytes| unsafe {
+                self.ptr.set_input_value(*handle, bytes.as_ptr(), 0);
+            }),
+            EndpointMessage::Event {
+                handle,
+                type_index,
+                value,
+            } => value.with_bytes(|bytes| {
+                self.ptr.add_input_event(*handle, *type_index, bytes);
+            }),
+        });
+    }
+
+    /// Sets the handler for messages written to the program's console.
+    ///
+    /// The handler is called during [`Performer::advance`]. Strings are resolved to
+    /// [`ValueRef::Text`] where possible. By default, messages are printed to stdout.
+    pub fn set_console_handler(&mut self, handler: impl FnMut(ValueRef<'_>) + Send + 'static) {
+        self.console_handler = Some(Box::new(handler));
+        self.console_delivered = false;
+    }
+
+    /// Resets the performer's internal state, as if it had just been created.
+    pub fn reset(&mut self) {
+        self.ptr.reset();
+        self.buffer.fill(0);
     }
 
     /// Returns information about a given endpoint.
@@ -79,12 +315,102 @@ impl Performer {
         self.endpoints.get(&endpoint.handle())
     }
 
+    /// Returns information about the endpoint with the given handle.
+    pub fn info_for_handle(&self, handle: EndpointHandle) -> Option<&EndpointInfo> {
+        self.endpoints.get(&handle)
+    }
+
+    /// Returns information about the endpoint with the given ID.
+    ///
+    /// Only endpoints that were requested from the engine before linking can be found.
+    pub fn endpoint_by_id(&self, id: impl AsRef<str>) -> Option<&EndpointInfo> {
+        let id = id.as_ref();
+        self.endpoints.values().find(|endpoint| endpoint.id() == id)
+    }
+
+    /// Returns the IDs of the endpoints available to the performer, in no particular order.
+    ///
+    /// Only endpoints that were requested from the engine before linking are included.
+    pub fn endpoint_ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.endpoints
+            .values()
+            .map(|endpoint| endpoint.id().as_ref())
+    }
+
+    /// The total number of input audio channels, summed across the input stream endpoints.
+    ///
+    /// Scalar streams count as one channel and vector streams as one channel per element. Only
+    /// endpoints that were requested from the engine before linking are counted.
+    pub fn input_channels(&self) -> usize {
+        self.count_channels(EndpointDirection::Input)
+    }
+
+    /// The total number of output audio channels, summed across the output stream endpoints.
+    ///
+    /// Scalar streams count as one channel and vector streams as one channel per element. Only
+    /// endpoints that were requested from the engine before linking are counted.
+    pub fn output_channels(&self) -> usize {
+        self.count_channels(EndpointDirection::Output)
+    }
+
+    fn count_channels(&self, direction: EndpointDirection) -> usize {
+        self.endpoints
+            .values()
+            .filter(|endpoint| endpoint.direction() == direction)
+            .filter_map(EndpointInfo::as_stream)
+            .filter_map(|stream| stream.ty().element_count())
+            .sum()
+    }
+
+    /// Create a reader for a primitive output value that can be read from other threads.
+    ///
+    /// The reader holds a snapshot of the value that is updated each time the performer
+    /// advances, so it can be used to monitor outputs without access to the performer.
+    pub fn output_value_reader<T>(
+        &mut self,
+        endpoint: Endpoint<OutputValue<T>>,
+    ) -> OutputValueReader<T>
+    where
+        T: AtomicOutputValue,
+    {
+        output_value_reader(self, endpoint)
+    }
+
+    /// Sets whether values written with [`Performer::set`] may use different object class names
+    /// to the endpoint's type.
+    ///
+    /// By default, objects must match the endpoint's type exactly. When enabled, objects with the
+    /// same layout are accepted regardless of their class names (see [`Type::compatible_with`]),
+    /// which is useful for structs reflected from Rust types. The setting is passed on to the
+    /// [`PerformerHandle`] when the performer is split.
+    ///
+    /// [`Type::compatible_with`]: crate::value::types::Type::compatible_with
+    pub fn set_ignore_class_names(&mut self, ignore_class_names: bool) {
+        self.ignore_class_names = ignore_class_names;
+    }
+
     /// Set the value of an endpoint.
     pub fn set<T>(&mut self, endpoint: Endpoint<InputValue<T>>, value: T) -> T::Output
     where
         T: SetInputValue,
     {
-        SetInputValue::set_input_value(self, endpoint, value)
+        SetInputValue::set_input_value(self, endpoint, value, 0)
+    }
+
+    /// Set the value of an endpoint, ramping to it over the given number of frames.
+    ///
+    /// Ramping smooths changes to parameters such as gains, avoiding clicks. Setting a value with
+    /// a ramp length of zero is the same as [`Performer::set`].
+    pub fn set_ramped<T>(
+        &mut self,
+        endpoint: Endpoint<InputValue<T>>,
+        value: T,
+        num_frames: u32,
+    ) -> T::Output
+    where
+        T: SetInputValue,
+    {
+        SetInputValue::set_input_value(self, endpoint, value, num_frames)
     }
 
     /// Get the value of an endpoint.
@@ -95,6 +421,45 @@ impl Performer {
         T::get_output_value(self, endpoint)
     }
 
+    /// Get the value of an output endpoint, converting it into a `U`.
+    ///
+    /// Returns [`EndpointError::DataTypeMismatch`] if the value can't be converted.
+    pub fn get_as<U>(&mut self, endpoint: Endpoint<OutputValue>) -> Result<U, EndpointError>
+    where
+        U: for<'a> TryFrom<ValueRef<'a>>,
+    {
+        let value = self
+            .get(endpoint)
+            .map_err(|_| EndpointError::DataTypeMismatch)?;
+
+        U::try_from(value).map_err(|_| EndpointError::DataTypeMismatch)
+    }
+
+    /// Copy the value of an output endpoint into a caller-provided buffer, returning its type.
+    ///
+    /// The buffer is resized to the size of the type, so it only allocates if it needs to grow.
+    /// Unlike [`Performer::get`], the value doesn't borrow the performer, and can be read later
+    /// with [`ValueRef::from_bytes`].
+    pub fn copy_output_into<T>(
+        &self,
+        Endpoint(endpoint): Endpoint<OutputValue<T>>,
+        buffer: &mut Vec<u8>,
+    ) -> &Type
+    where
+        T: ValueEndpointType,
+    {
+        let ty = self
+            .endpoints
+            .get(&endpoint.handle())
+            .and_then(EndpointInfo::as_value)
+            .map(|value_endpoint| value_endpoint.ty())
+            .expect("failed to determine endpoint type");
+
+        buffer.resize(ty.size(), 0);
+        self.ptr.copy_output_value(endpoint.handle(), buffer);
+        ty
+    }
+
     /// Post an event to an endpoint.
     pub fn post<'a>(
         &mut self,
@@ -104,15 +469,98 @@ impl Performer {
         post_event(self, endpoint, event.into())
     }
 
-    /// Fetch the events received from an endpoint.
+    /// Post a sequence of events to an endpoint.
+    ///
+    /// Stops at the first event that fails to post, returning its error. Events before it will
+    /// have been posted.
+    pub fn post_all<'a, T>(
+        &mut self,
+        endpoint: Endpoint<InputEvent>,
+        events: impl IntoIterator<Item = T>,
+    ) -> Result<(), EndpointError>
+    where
+        T: Into<ValueRef<'a>>,
+    {
+        post_events(self, endpoint, events.into_iter().map(Into::into))
+    }
+
+    /// Fetch the events received from an endpoint, returning the number of events fetched.
     pub fn fetch(
         &mut self,
         endpoint: Endpoint<OutputEvent>,
         callback: impl FnMut(usize, ValueRef<'_>),
-    ) -> Result<(), EndpointError> {
+    ) -> Result<usize, EndpointError> {
         fetch_events(self, endpoint, callback)
     }
 
+    /// Collect the events received from an endpoint, along with their frame offsets.
+    ///
+    /// Unlike [`Performer::fetch`], the events are owned so can be kept after the next block is
+    /// rendered. This allocates, so isn't suitable for real-time use.
+    pub fn collect_events(
+        &mut self,
+        endpoint: Endpoint<OutputEvent>,
+    ) -> Result<Vec<(usize, Value)>, EndpointError> {
+        let mut events = Vec::new();
+        fetch_events(self, endpoint, |frame_offset, event| {
+            events.push((frame_offset, event.to_owned()));
+        })?;
+        Ok(events)
+    }
+
+    /// Fetch the events received from every output event endpoint in a single pass.
+    ///
+    /// Only endpoints that have been requested from the engine are visited, in no particular
+    /// order. Returns the total number of events passed to the callback.
+    pub fn fetch_all(
+        &mut self,
+        callback: impl FnMut(Endpoint<OutputEvent>, usize, ValueRef<'_>),
+    ) -> usize {
+        fetch_all_events(self, callback)
+    }
+
+    /// Fetch the events received from an endpoint, decoding each one into a `T`.
+    ///
+    /// Events that can't be converted into a `T` are skipped. Returns the number of events that
+    /// were decoded and passed to the callback.
+    pub fn fetch_typed<T>(
+        &mut self,
+        endpoint: Endpoint<OutputEvent>,
+        mut callback: impl FnMut(usize, T),
+    ) -> Result<usize, EndpointError>
+    where
+        T: for<'a> TryFrom<ValueRef<'a>>,
+    {
+        let mut count = 0;
+        fetch_events(self, endpoint, |frame_offset, value| {
+            if let Ok(value) = T::try_from(value) {
+                callback(frame_offset, value);
+                count += 1;
+            }
+        })?;
+        Ok(count)
+    }
+
+    /// Post a MIDI message to an event endpoint that accepts `std::midi::Message` (or `int32`).
+    pub fn post_midi(
+        &mut self,
+        endpoint: Endpoint<InputEvent>,
+        message: [u8; 3],
+    ) -> Result<(), EndpointError> {
+        post_midi(self, endpoint, message)
+    }
+
+    /// Fetch the MIDI messages received from an event endpoint, returning the number fetched.
+    ///
+    /// Events that aren't MIDI messages are skipped.
+    pub fn fetch_midi(
+        &mut self,
+        endpoint: Endpoint<OutputEvent>,
+        callback: impl FnMut(usize, [u8; 3]),
+    ) -> Result<usize, EndpointError> {
+        fetch_midi(self, endpoint, callback)
+    }
+
     /// Read frames from an input stream.
     pub fn read<T>(&self, endpoint: Endpoint<OutputStream<T>>, buffer: &mut [T])
     where
@@ -121,6 +569,42 @@ impl Performer {
         read_stream(self, endpoint, buffer)
     }
 
+    /// Read frames from an output stream, checking that the buffer is exactly one block long.
+    ///
+    /// Returns [`EndpointError::BufferLengthMismatch`] if the buffer's length differs from
+    /// [`Performer::block_size`].
+    pub fn try_read<T>(
+        &self,
+        endpoint: Endpoint<OutputStream<T>>,
+        buffer: &mut [T],
+    ) -> Result<(), EndpointError>
+    where
+        T: StreamType,
+    {
+        self.check_block_length(buffer.len())?;
+        read_stream(self, endpoint, buffer);
+        Ok(())
+    }
+
+    /// Read a whole block of frames from an output stream.
+    pub fn read_block<T>(&self, endpoint: Endpoint<OutputStream<T>>) -> Vec<T>
+    where
+        T: StreamType + Default,
+    {
+        let mut buffer = Vec::new();
+        self.read_block_into(endpoint, &mut buffer);
+        buffer
+    }
+
+    /// Read a whole block of frames from an output stream, resizing the buffer to the block size.
+    pub fn read_block_into<T>(&self, endpoint: Endpoint<OutputStream<T>>, buffer: &mut Vec<T>)
+    where
+        T: StreamType + Default,
+    {
+        buffer.resize(self.block_size() as usize, T::default());
+        read_stream(self, endpoint, buffer)
+    }
+
     /// Write frames to an output stream.
     pub fn write<T>(&self, endpoint: Endpoint<InputStream<T>>, buffer: &[T])
     where
@@ -129,9 +613,73 @@ impl Performer {
         write_stream(self, endpoint, buffer)
     }
 
-    /// Returns the number of times the performer has over/under-run.
+    /// Write frames to an input stream, checking that the buffer is exactly one block long.
+    ///
+    /// Returns [`EndpointError::BufferLengthMismatch`] if the buffer's length differs from
+    /// [`Performer::block_size`].
+    pub fn try_write<T>(
+        &self,
+        endpoint: Endpoint<InputStream<T>>,
+        buffer: &[T],
+    ) -> Result<(), EndpointError>
+    where
+        T: StreamType,
+    {
+        self.check_block_length(buffer.len())?;
+        write_stream(self, endpoint, buffer);
+        Ok(())
+    }
+
+    fn check_block_length(&self, len: usize) -> Result<(), EndpointError> {
+        let expected = self.block_size() as usize;
+        if len != expected {
+            return Err(EndpointError::BufferLengthMismatch {
+                expected,
+                found: len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Write separate per-channel buffers to a vector input stream, interleaving them into frames.
+    ///
+    /// The number of channels must match the stream's vector size, and every channel must have
+    /// the same length.
+    pub fn write_planar<T, const N: usize>(
+        &mut self,
+        endpoint: Endpoint<InputStream<[T; N]>>,
+        channels: &[&[T]],
+    ) -> Result<(), EndpointError>
+    where
+        T: StreamType + IsScalar,
+    {
+        write_planar_stream(self, endpoint, channels)
+    }
+
+    /// Read frames from a vector output stream, deinterleaving them into per-channel buffers.
+    ///
+    /// The number of channels must match the stream's vector size, and every channel must have
+    /// the same length.
+    pub fn read_planar<T, const N: usize>(
+        &mut self,
+        endpoint: Endpoint<OutputStream<[T; N]>>,
+        channels: &mut [&mut [T]],
+    ) -> Result<(), EndpointError>
+    where
+        T: StreamType + IsScalar,
+    {
+        read_planar_stream(self, endpoint, channels)
+    }
+
+    /// Returns the number of times the performer has over/under-run since it was created, or since
+    /// the last call to [`Performer::reset_xruns`].
     pub fn get_xruns(&self) -> usize {
-        self.ptr.get_xruns()
+        self.ptr.get_xruns().saturating_sub(self.xrun_baseline)
+    }
+
+    /// Resets the count returned by [`Performer::get_xruns`] to zero.
+    pub fn reset_xruns(&mut self) {
+        self.xrun_baseline = self.ptr.get_xruns();
     }
 
     /// Returns the maximum number of frames that can be processed in a single call to `advance`.
@@ -139,11 +687,38 @@ impl Performer {
         self.ptr.get_max_block_size()
     }
 
+    /// Returns the maximum number of events that can be queued for an endpoint in a single block.
+    ///
+    /// This is useful for sizing client-side event queues, such as with
+    /// [`Performer::split_with_capacity`], so they can't hold more events than the performer can
+    /// take.
+    pub fn event_buffer_size(&self) -> u32 {
+        self.ptr.get_event_buffer_size()
+    }
+
     /// Returns the performers internal latency in frames.
     pub fn get_latency(&self) -> f64 {
         self.ptr.get_latency()
     }
 
+    /// Returns the performers internal latency in frames (samples).
+    pub fn latency_samples(&self) -> f64 {
+        self.get_latency()
+    }
+
+    /// Returns the performers internal latency in seconds.
+    ///
+    /// Returns `None` if the engine was built without a sample rate.
+    pub fn latency_seconds(&self) -> Option<f64> {
+        self.sample_rate()
+            .map(|sample_rate| self.get_latency() / sample_rate)
+    }
+
+    /// Returns the sample rate the engine was built with, if one was set.
+    pub fn sample_rate(&self) -> Option<f64> {
+        (self.sample_rate > 0.0).then_some(self.sample_rate)
+    }
+
     /// Returns the string associated with a handle.
     pub fn get_string(&self, StringHandle(value): StringHandle) -> Option<&str> {
         self.ptr.get_string_for_handle(value)
@@ -168,6 +743,32 @@ pub enum EndpointError {
     /// The data type does not match the expected type.
     #[error("data type mismatch")]
     DataTypeMismatch,
+
+    /// The value can't be passed to the performer, e.g. host-owned text which has no handle.
+    #[error("unsupported value")]
+    UnsupportedValue,
+
+    /// The queue of pending updates is full.
+    #[error("message queue full")]
+    QueueFull,
+
+    /// The number of channels does not match the stream's vector size.
+    #[error("channel count mismatch")]
+    ChannelCountMismatch,
+
+    /// The channel buffers are not all the same length.
+    #[error("channel length mismatch")]
+    ChannelLengthMismatch,
+
+    /// The buffer's length does not match the performer's block size.
+    #[error("buffer length mismatch (expected {expected} frames, found {found})")]
+    BufferLengthMismatch {
+        /// The performer's block size.
+        expected: usize,
+
+        /// The length of the buffer.
+        found: usize,
+    },
 }
 
 #[doc(hidden)]
@@ -0,0 +1,144 @@
+//! A single-producer, single-consumer queue for sending endpoint updates to a performer.
+
+use {
+    crate::{
+        endpoint::{EndpointHandle, EndpointTypeIndex},
+        value::Value,
+    },
+    rtrb::{Consumer, Producer, PushError, RingBuffer},
+};
+
+/// An update to an input endpoint, sent from a [`PerformerHandle`](super::PerformerHandle).
+pub(crate) enum EndpointMessage {
+    /// Set the value of an input value endpoint.
+    Value {
+        handle: EndpointHandle,
+        value: Value,
+    },
+
+    /// Post an event to an input event endpoint.
+    Event {
+        handle: EndpointHandle,
+        type_index: EndpointTypeIndex,
+        value: Value,
+    },
+}
+
+/// The sending half of the queue.
+pub(crate) struct Sender {
+    messages: Producer<EndpointMessage>,
+    returned: Consumer<EndpointMessage>,
+}
+
+/// The receiving half of the queue.
+///
+/// Messages can own heap allocations (such as array and object values), so once applied they are
+/// sent back on a second queue to be dropped by the [`Sender`], rather than on the audio thread.
+pub(crate) struct Receiver {
+    messages: Consumer<EndpointMessage>,
+    returned: Producer<EndpointMessage>,
+}
+
+/// Create a new queue with the given capacity.
+pub(crate) fn channel(capacity: usize) -> (Sender, Receiver) {
+    let (producer, consumer) = RingBuffer::new(capacity);
+
+    // The sender empties the return queue before each send, so at most `capacity` messages can
+    // be in flight between the two queues, plus the one just sent. This leaves room to return
+    // every received message.
+    let (return_producer, return_consumer) = RingBuffer::new(capacity + 1);
+
+    (
+        Sender {
+            messages: producer,
+            returned: return_consumer,
+        },
+        Receiver {
+            messages: consumer,
+            returned: return_producer,
+        },
+    )
+}
+
+impl Sender {
+    /// Push a message onto the queue, returning it if the queue is full.
+    ///
+    /// Messages already applied by the receiver are dropped first.
+    pub(crate) fn send(&mut self, message: EndpointMessage) -> Result<(), EndpointMessage> {
+        while self.returned.pop().is_ok() {}
+
+        self.messages
+            .push(message)
+            .map_err(|PushError::Full(message)| message)
+    }
+
+    /// The number of messages waiting to be received.
+    pub(crate) fn len(&self) -> usize {
+        self.messages.buffer().capacity() - self.messages.slots()
+    }
+
+    /// The number of messages that can be sent before the queue is full.
+    pub(crate) fn remaining_capacity(&self) -> usize {
+        self.messages.slots()
+    }
+}
+
+impl Receiver {
+    /// Call `f` with each queued message, in order, then return it to the sender to be dropped.
+    pub(crate) fn for_each(&mut self, mut f: impl FnMut(&EndpointMessage)) {
+        while let Ok(message) = self.messages.pop() {
+            f(&message);
+
+            let returned = self.returned.push(message);
+            debug_assert!(returned.is_ok(), "the return queue should never be full");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, crate::value::ArrayValue};
+
+    fn message(value: impl Into<Value>) -> EndpointMessage {
+        EndpointMessage::Value {
+            handle: EndpointHandle::from(0),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn received_messages_are_returned_to_the_sender() {
+        let (mut sender, mut receiver) = channel(2);
+
+        let array = Value::from(ArrayValue::from_slice(&[1.0_f32, 2.0, 3.0]));
+        assert!(sender.send(message(array.clone())).is_ok());
+
+        let mut received = vec![];
+        receiver.for_each(|message| {
+            if let EndpointMessage::Value { value, .. } = message {
+                received.push(value.clone());
+            }
+        });
+        assert_eq!(received, [array]);
+        assert_eq!(sender.len(), 0);
+        assert_eq!(sender.returned.slots(), 1);
+
+        assert!(sender.send(message(5)).is_ok());
+        assert_eq!(sender.returned.slots(), 0);
+    }
+
+    #[test]
+    fn the_return_queue_has_room_for_a_full_queue() {
+        let capacity = 4;
+        let (mut sender, mut receiver) = channel(capacity);
+
+        for round in 0..3 {
+            while sender.send(message(round)).is_ok() {}
+
+            let mut count = 0;
+            receiver.for_each(|_| count += 1);
+            assert_eq!(count, capacity);
+            assert_eq!(sender.returned.slots(), capacity);
+        }
+    }
+}
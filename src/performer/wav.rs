@@ -0,0 +1,73 @@
+//! Rendering performer output to WAV files.
+
+use {
+    crate::performer::{Endpoint, OutputStream, Performer, StreamType},
+    hound::{SampleFormat, WavSpec, WavWriter},
+    std::{mem, path::Path},
+};
+
+/// An error that can occur when rendering to a WAV file.
+#[derive(thiserror::Error, Debug)]
+pub enum RenderError {
+    /// The performer's engine was built without a sample rate.
+    #[error("The engine was built without a sample rate")]
+    MissingSampleRate,
+
+    /// Failed to write the WAV file.
+    #[error("Failed to write WAV file: {0}")]
+    FailedToWrite(#[from] hound::Error),
+}
+
+/// Render `num_frames` frames of an output stream to a 32-bit float WAV file.
+///
+/// The performer is advanced in blocks of up to its maximum block size. Scalar streams are
+/// written as mono files, and vector streams (such as `float<2>`) as one channel per element.
+/// The file's sample rate is the one the engine was built with.
+pub fn render_to_wav<T>(
+    performer: &mut Performer,
+    endpoint: Endpoint<OutputStream<T>>,
+    num_frames: usize,
+    path: impl AsRef<Path>,
+) -> Result<(), RenderError>
+where
+    T: StreamType<Element = f32>,
+{
+    let sample_rate = performer
+        .sample_rate()
+        .ok_or(RenderError::MissingSampleRate)?;
+
+    let num_channels = mem::size_of::<T>() / mem::size_of::<f32>();
+    let spec = WavSpec {
+        channels: num_channels as u16,
+        sample_rate: sample_rate.round() as u32,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(path, spec)?;
+
+    let max_block_size = performer.get_max_block_size().max(1) as usize;
+    let mut samples = vec![0.0_f32; max_block_size * num_channels];
+
+    let mut remaining = num_frames;
+    while remaining > 0 {
+        let block_size = remaining.min(max_block_size);
+        performer.advance_frames(block_size as u32);
+
+        let samples = &mut samples[..block_size * num_channels];
+
+        // SAFETY: stream types with `f32` elements are `f32`s or arrays of them, so a frame has
+        // the same alignment as `f32` and is made up of exactly `num_channels` samples.
+        let frames =
+            unsafe { std::slice::from_raw_parts_mut(samples.as_mut_ptr().cast::<T>(), block_size) };
+        performer.read(endpoint, frames);
+
+        for &sample in samples.iter() {
+            writer.write_sample(sample)?;
+        }
+
+        remaining -= block_size;
+    }
+
+    writer.finalize()?;
+    Ok(())
+}
@@ -1,9 +1,10 @@
-use crate::{diagnostic::DiagnosticMessage, ffi::ProgramPtr};
+use crate::{diagnostic::Diagnostics, ffi::ProgramPtr};
 
 /// A Cmajor program.
 #[derive(Debug)]
 pub struct Program {
     pub(crate) inner: ProgramPtr,
+    pub(crate) warnings: Diagnostics,
 }
 
 /// An error that can occur when parsing a Cmajor program.
@@ -11,23 +12,101 @@ pub struct Program {
 pub enum ParseError {
     /// An error occurred while parsing a program.
     #[error("Error parsing program: {0:?}")]
-    ParserError(Box<DiagnosticMessage>),
+    ParserError(Diagnostics),
 
     /// An error occurred whilst parsing the error from the library.
     #[error(transparent)]
     FailedToParseError(#[from] serde_json::Error),
+
+    /// An error occurred whilst reading a source file.
+    #[error(transparent)]
+    FailedToReadFile(#[from] std::io::Error),
+}
+
+/// Options for parsing a Cmajor program.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ParseOptions {
+    warnings_as_errors: bool,
+}
+
+impl ParseOptions {
+    /// Treat warnings as errors.
+    ///
+    /// By default, a program whose diagnostics only contain warnings or notes is accepted, and the
+    /// diagnostics are kept in [`Program::warnings`]. With this option set, any diagnostics fail
+    /// the parse.
+    pub fn with_warnings_as_errors(mut self, warnings_as_errors: bool) -> Self {
+        self.warnings_as_errors = warnings_as_errors;
+        self
+    }
+
+    fn should_fail(&self, diagnostics: &Diagnostics) -> bool {
+        self.warnings_as_errors || diagnostics.has_errors()
+    }
 }
 
 impl Program {
-    pub(crate) fn parse(&mut self, program: impl AsRef<str>) -> Result<(), ParseError> {
-        let file_name: Option<&str> = None;
+    /// The warnings and notes reported while parsing the program's source units.
+    pub fn warnings(&self) -> &Diagnostics {
+        &self.warnings
+    }
 
+    /// Parse an additional source unit into the program.
+    ///
+    /// The name is reported as the file name in any diagnostics.
+    pub fn add_source(
+        &mut self,
+        name: impl AsRef<str>,
+        program: impl AsRef<str>,
+    ) -> Result<(), ParseError> {
+        self.parse(Some(name.as_ref()), program, ParseOptions::default())
+    }
+
+    pub(crate) fn parse(
+        &mut self,
+        file_name: Option<&str>,
+        program: impl AsRef<str>,
+        options: ParseOptions,
+    ) -> Result<(), ParseError> {
         match self.inner.parse(file_name, program) {
             Ok(()) => Ok(()),
             Err(error) => {
-                let parser_error = serde_json::from_str(error.to_str())?;
-                Err(ParseError::ParserError(Box::new(parser_error)))
+                let diagnostics: Diagnostics = serde_json::from_str(error.to_str())?;
+                if options.should_fail(&diagnostics) {
+                    return Err(ParseError::ParserError(diagnostics));
+                }
+                self.warnings.append(diagnostics);
+                Ok(())
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diagnostics(severity: &str) -> Diagnostics {
+        serde_json::from_value(serde_json::json!({
+            "severity": severity,
+            "message": "Unused variable",
+            "fileName": "",
+            "sourceLine": "",
+            "columnNumber": 1,
+            "lineNumber": 1,
+            "annotatedLine": "",
+            "fullDescription": ""
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn warnings_can_be_promoted_to_errors() {
+        let options = ParseOptions::default();
+        assert!(options.should_fail(&diagnostics("error")));
+        assert!(!options.should_fail(&diagnostics("warning")));
+
+        let options = options.with_warnings_as_errors(true);
+        assert!(options.should_fail(&diagnostics("warning")));
+    }
+}
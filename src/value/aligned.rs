@@ -0,0 +1,123 @@
+use {
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    smallvec::SmallVec,
+    std::{
+        fmt::{self, Debug, Formatter},
+        mem,
+        ops::{Deref, DerefMut},
+    },
+};
+
+/// A growable byte buffer whose data is aligned for any Cmajor scalar.
+///
+/// Values are stored as their raw bytes, and keeping those bytes aligned to 8 means arrays of
+/// scalars can be borrowed in place as slices of `i64` or `f64`.
+#[derive(Clone, Default)]
+pub(crate) struct AlignedBytes {
+    words: SmallVec<[u64; 2]>,
+    len: usize,
+}
+
+impl AlignedBytes {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn zeroed(len: usize) -> Self {
+        let mut bytes = Self::new();
+        bytes.resize(len);
+        bytes
+    }
+
+    pub(crate) fn from_slice(data: &[u8]) -> Self {
+        let mut bytes = Self::new();
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    pub(crate) fn extend_from_slice(&mut self, data: &[u8]) {
+        let start = self.len;
+        self.resize(start + data.len());
+        self[start..].copy_from_slice(data);
+    }
+
+    /// Resize the buffer, filling any new bytes with zeroes.
+    fn resize(&mut self, len: usize) {
+        self.words.resize(len.div_ceil(mem::size_of::<u64>()), 0);
+        if len < self.len {
+            // Keep the bytes past the end zeroed, so growing again yields zeroes.
+            let end = self.words.len() * mem::size_of::<u64>();
+            self.len = end;
+            self[len..end].fill(0);
+        }
+        self.len = len;
+    }
+}
+
+impl Deref for AlignedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the words hold at least `len` initialised bytes, and `u8` has no alignment
+        // requirements.
+        unsafe { std::slice::from_raw_parts(self.words.as_ptr().cast(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as for `deref`, and the words are borrowed mutably.
+        unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl PartialEq for AlignedBytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Debug for AlignedBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl Serialize for AlignedBytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AlignedBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<u8>::deserialize(deserializer).map(|data| Self::from_slice(&data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bytes_are_aligned_for_scalars() {
+        let mut bytes = AlignedBytes::new();
+        for len in [0, 3, 16, 17, 100] {
+            bytes.extend_from_slice(&vec![0xAB; len]);
+            assert_eq!(bytes.as_ptr().align_offset(mem::align_of::<u64>()), 0);
+        }
+        assert_eq!(bytes.len(), 136);
+        assert!(bytes.iter().all(|&byte| byte == 0xAB));
+
+        bytes.resize(5);
+        bytes.resize(8);
+        assert_eq!(*bytes, [0xAB, 0xAB, 0xAB, 0xAB, 0xAB, 0, 0, 0]);
+        assert_eq!(AlignedBytes::zeroed(3), AlignedBytes::from_slice(&[0; 3]));
+    }
+}
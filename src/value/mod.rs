@@ -1,9 +1,15 @@
 //! Support for Cmajor values.
 
+mod aligned;
 pub mod types;
 mod values;
 
-pub use values::{
-    ArrayValue, ArrayValueRef, Complex32, Complex64, ObjectValue, ObjectValueRef, StringHandle,
-    Value, ValueRef,
+pub(crate) use aligned::AlignedBytes;
+pub use {
+    cmajor_macros::CmajorValue,
+    values::{
+        ArraySlice, ArrayValue, ArrayValueError, ArrayValueRef, Complex32, Complex64,
+        FromJsonError, ObjectValue, ObjectValueBuilder, ObjectValueError, ObjectValueRef,
+        StringHandle, Value, ValueRef,
+    },
 };
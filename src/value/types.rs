@@ -113,6 +113,13 @@ pub struct Array {
     len: usize,
 }
 
+/// The memory layout of a [`Type`], as returned by [`Type::layout`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeLayout {
+    size: usize,
+    fields: Vec<(String, usize, Type)>,
+}
+
 impl Type {
     /// The size of the type in bytes.
     pub fn size(&self) -> usize {
@@ -155,6 +162,64 @@ impl Type {
         }
     }
 
+    /// Returns `true` if the type is a primitive.
+    pub fn is_primitive(&self) -> bool {
+        self.as_ref().is_primitive()
+    }
+
+    /// Returns `true` if the type is an array.
+    pub fn is_array(&self) -> bool {
+        self.as_ref().is_array()
+    }
+
+    /// Returns `true` if the type is an object.
+    pub fn is_object(&self) -> bool {
+        self.as_ref().is_object()
+    }
+
+    /// The number of elements in the type.
+    ///
+    /// See [`TypeRef::element_count`].
+    pub fn element_count(&self) -> Option<usize> {
+        self.as_ref().element_count()
+    }
+
+    /// Returns the layout of the type, with the absolute byte offset of each of its values.
+    ///
+    /// Objects are flattened into their fields (e.g. `a.b`), as are arrays of objects (e.g.
+    /// `a[1].b`). Other arrays are treated as a single value. Values are packed with no padding,
+    /// matching the layout of values passed to and from the engine.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::types::{Array, Object, Type};
+    /// let inner = Object::new("Inner").with_field("x", Type::Float32);
+    /// let ty: Type = Object::new("Outer")
+    ///     .with_field("a", Type::Int64)
+    ///     .with_field("b", Array::new(inner, 2))
+    ///     .into();
+    ///
+    /// let layout = ty.layout();
+    /// assert_eq!(layout.size(), 16);
+    ///
+    /// let offsets: Vec<_> = layout
+    ///     .fields()
+    ///     .iter()
+    ///     .map(|(path, offset, _)| (path.as_str(), *offset))
+    ///     .collect();
+    /// assert_eq!(offsets, [("a", 0), ("b[0].x", 8), ("b[1].x", 12)]);
+    /// ```
+    pub fn layout(&self) -> TypeLayout {
+        let mut fields = Vec::new();
+        collect_layout(self, String::new(), 0, &mut fields);
+
+        TypeLayout {
+            size: self.size(),
+            fields,
+        }
+    }
+
     /// Returns the corresponding [`TypeId`] for the type (if any).
     pub(crate) fn type_id(&self) -> Option<TypeId> {
         match self {
@@ -178,9 +243,130 @@ impl Type {
                 .type_id()
                 .expect("primitive types always have a type id")
     }
+
+    /// Read a type in choc's serialised format, advancing the buffer past it.
+    ///
+    /// Vectors are read as arrays, as are arrays whose groups all share an element type. An
+    /// array with no groups is read as an empty array of `void`. Returns `None` if the buffer
+    /// doesn't contain a type that can be represented, in which case it may have been partly
+    /// consumed.
+    pub fn deserialise_from_choc_type(buffer: &mut &[u8]) -> Option<Type> {
+        let (&tag, rest) = buffer.split_first()?;
+        *buffer = rest;
+
+        let ty = match tag {
+            0 => Type::Void,
+            1 => Type::Int32,
+            2 => Type::Int64,
+            3 => Type::Float32,
+            4 => Type::Float64,
+            5 => Type::Bool,
+            6 => {
+                let len = usize::try_from(read_packed_int(buffer)?).ok()?;
+                let elem_ty = Type::deserialise_from_choc_type(buffer)?;
+                Array::new(elem_ty, len).into()
+            }
+            7 => {
+                let num_groups = read_packed_int(buffer)?;
+                let mut array: Option<Array> = None;
+                for _ in 0..num_groups {
+                    let len = usize::try_from(read_packed_int(buffer)?).ok()?;
+                    let elem_ty = Type::deserialise_from_choc_type(buffer)?;
+                    array = match array {
+                        None => Some(Array::new(elem_ty, len)),
+                        Some(array) if array.elem_ty() == &elem_ty => {
+                            Some(array.resized(array.len().checked_add(len)?))
+                        }
+                        Some(_) => return None,
+                    };
+                }
+                array.unwrap_or_else(|| Array::new(Type::Void, 0)).into()
+            }
+            8 => {
+                let num_fields = read_packed_int(buffer)?;
+                let mut object = Object::new(read_null_terminated_string(buffer)?);
+                for _ in 0..num_fields {
+                    let ty = Type::deserialise_from_choc_type(buffer)?;
+                    object.add_field(read_null_terminated_string(buffer)?, ty);
+                }
+                object.into()
+            }
+            9 => Type::String,
+            _ => return None,
+        };
+
+        Some(ty)
+    }
+
+    /// Returns `true` if values of the two types have the same layout and field names, ignoring
+    /// the class names of objects.
+    ///
+    /// Array elements and object fields are compared recursively. See
+    /// [`TypeRef::structurally_eq`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::types::{Object, Type};
+    /// let a = Type::from(Object::new("Params").with_field("gain", Type::Float32));
+    /// let b = Type::from(Object::new("my_crate::Params").with_field("gain", Type::Float32));
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.compatible_with(&b));
+    /// ```
+    pub fn compatible_with(&self, other: &Type) -> bool {
+        self.as_ref().structurally_eq(other.as_ref())
+    }
 }
 
-fn write_packed_int(mut buffer: impl BufMut, mut value: u64) {
+fn collect_layout(ty: &Type, path: String, offset: usize, fields: &mut Vec<(String, usize, Type)>) {
+    match ty {
+        Type::Object(object) => {
+            for field in object.fields() {
+                let path = if path.is_empty() {
+                    field.name().to_owned()
+                } else {
+                    format!("{path}.{}", field.name())
+                };
+                collect_layout(field.ty(), path, offset + field.offset(), fields);
+            }
+        }
+        Type::Array(array) if contains_object(array.elem_ty()) => {
+            let elem_size = array.elem_ty().size();
+            for index in 0..array.len() {
+                collect_layout(
+                    array.elem_ty(),
+                    format!("{path}[{index}]"),
+                    offset + index * elem_size,
+                    fields,
+                );
+            }
+        }
+        ty => fields.push((path, offset, ty.clone())),
+    }
+}
+
+fn contains_object(ty: &Type) -> bool {
+    match ty {
+        Type::Object(_) => true,
+        Type::Array(array) => contains_object(array.elem_ty()),
+        _ => false,
+    }
+}
+
+impl TypeLayout {
+    /// The total size of the type in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The path, absolute byte offset and type of each value within the type.
+    pub fn fields(&self) -> &[(String, usize, Type)] {
+        &self.fields
+    }
+}
+
+pub(crate) fn write_packed_int(mut buffer: impl BufMut, mut value: u64) {
     while value >= 0x80 {
         buffer.put_u8((value & 0x7F) as u8 | 0x80);
         value >>= 7;
@@ -188,11 +374,42 @@ fn write_packed_int(mut buffer: impl BufMut, mut value: u64) {
     buffer.put_u8(value as u8);
 }
 
-fn write_null_terminated_string(mut buffer: impl BufMut, string: impl AsRef<str>) {
+pub(crate) fn write_null_terminated_string(mut buffer: impl BufMut, string: impl AsRef<str>) {
     buffer.put_slice(string.as_ref().as_bytes());
     buffer.put_u8(0);
 }
 
+/// Read an integer written by [`write_packed_int`], advancing the buffer past it.
+///
+/// Returns `None` if the buffer ends before the integer does, or if it doesn't fit in a `u64`.
+pub(crate) fn read_packed_int(buffer: &mut &[u8]) -> Option<u64> {
+    let mut value = 0_u64;
+    let mut shift = 0;
+
+    loop {
+        let (&byte, rest) = buffer.split_first()?;
+        *buffer = rest;
+
+        let bits = u64::from(byte & 0x7F);
+        if shift >= u64::BITS || bits.leading_zeros() < shift {
+            return None;
+        }
+
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
+
+fn read_null_terminated_string<'a>(buffer: &mut &'a [u8]) -> Option<&'a str> {
+    let end = buffer.iter().position(|&byte| byte == 0)?;
+    let string = std::str::from_utf8(&buffer[..end]).ok()?;
+    *buffer = &buffer[end + 1..];
+    Some(string)
+}
+
 impl TypeRef<'_> {
     /// The size of the type in bytes.
     pub fn size(&self) -> usize {
@@ -209,6 +426,114 @@ impl TypeRef<'_> {
         }
     }
 
+    /// Returns `true` if the type is a primitive.
+    pub fn is_primitive(&self) -> bool {
+        matches!(
+            self,
+            TypeRef::Void
+                | TypeRef::Bool
+                | TypeRef::Int32
+                | TypeRef::Int64
+                | TypeRef::Float32
+                | TypeRef::Float64
+        )
+    }
+
+    /// If the type is a primitive, return it.
+    pub fn as_primitive(&self) -> Option<Primitive> {
+        match self {
+            TypeRef::Void => Some(Primitive::Void),
+            TypeRef::Bool => Some(Primitive::Bool),
+            TypeRef::Int32 => Some(Primitive::Int32),
+            TypeRef::Int64 => Some(Primitive::Int64),
+            TypeRef::Float32 => Some(Primitive::Float32),
+            TypeRef::Float64 => Some(Primitive::Float64),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the type is an array.
+    pub fn is_array(&self) -> bool {
+        matches!(self, TypeRef::Array(_))
+    }
+
+    /// Returns `true` if the type is an object.
+    pub fn is_object(&self) -> bool {
+        matches!(self, TypeRef::Object(_))
+    }
+
+    /// The number of elements in the type.
+    ///
+    /// For arrays this is the length of the array. Non-void primitives count as a single element,
+    /// matching the extent of a scalar stream. Other types return `None`.
+    pub fn element_count(&self) -> Option<usize> {
+        match self {
+            TypeRef::Array(array) => Some(array.len()),
+            TypeRef::Bool
+            | TypeRef::Int32
+            | TypeRef::Int64
+            | TypeRef::Float32
+            | TypeRef::Float64 => Some(1),
+            TypeRef::Void | TypeRef::String | TypeRef::Object(_) => None,
+        }
+    }
+
+    /// Returns `true` if the types have the same shape, ignoring the class names of objects.
+    ///
+    /// Objects match if their fields have the same names and types, in the same order.
+    pub fn structurally_eq(&self, other: TypeRef<'_>) -> bool {
+        match (*self, other) {
+            (TypeRef::Array(a), TypeRef::Array(b)) => {
+                a.len() == b.len() && a.elem_ty().as_ref().structurally_eq(b.elem_ty().as_ref())
+            }
+            (TypeRef::Object(a), TypeRef::Object(b)) => {
+                a.fields.len() == b.fields.len()
+                    && a.fields().zip(b.fields()).all(|(a, b)| {
+                        a.name() == b.name()
+                            && a.offset() == b.offset()
+                            && a.ty().as_ref().structurally_eq(b.ty().as_ref())
+                    })
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// The name of the type as it would be written in Cmajor.
+    ///
+    /// Arrays are written as `elem[len]`, or `elem[]` if they have no fixed length, and objects
+    /// use their class name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::types::{Array, Object, Type};
+    /// assert_eq!(Type::Int32.as_ref().type_name(), "int32");
+    ///
+    /// let ty = Type::from(Array::new(Type::Float64, 4));
+    /// assert_eq!(ty.as_ref().type_name(), "float64[4]");
+    ///
+    /// let ty = Type::from(Object::new("MyStruct"));
+    /// assert_eq!(ty.as_ref().type_name(), "MyStruct");
+    /// ```
+    pub fn type_name(&self) -> String {
+        match self {
+            TypeRef::Void => "void".to_owned(),
+            TypeRef::Bool => "bool".to_owned(),
+            TypeRef::Int32 => "int32".to_owned(),
+            TypeRef::Int64 => "int64".to_owned(),
+            TypeRef::Float32 => "float32".to_owned(),
+            TypeRef::Float64 => "float64".to_owned(),
+            TypeRef::String => "string".to_owned(),
+            TypeRef::Array(array) if array.is_empty() => {
+                format!("{}[]", array.elem_ty().as_ref().type_name())
+            }
+            TypeRef::Array(array) => {
+                format!("{}[{}]", array.elem_ty().as_ref().type_name(), array.len())
+            }
+            TypeRef::Object(object) => object.class().to_owned(),
+        }
+    }
+
     /// Convert the type reference into an owned [`Type`].
     pub fn to_owned(&self) -> Type {
         match *self {
@@ -232,11 +557,16 @@ impl TypeRef<'_> {
             TypeRef::Float32 => vec![3],
             TypeRef::Float64 => vec![4],
             TypeRef::Bool => vec![5],
-            TypeRef::String => todo!("serialising string types is not yet supported"),
+            TypeRef::String => vec![9],
             TypeRef::Array(array) => {
                 let mut buffer = vec![];
                 buffer.put_u8(7);
-                buffer.put_u8(if array.is_empty() { 0 } else { 1 });
+                if array.is_empty() {
+                    // An empty array has no groups, so there is no length or element type.
+                    buffer.put_u8(0);
+                    return buffer;
+                }
+                buffer.put_u8(1);
                 write_packed_int(&mut buffer, array.len() as u64);
                 buffer.put_slice(array.elem_ty().as_ref().serialise_as_choc_type().as_slice());
                 buffer
@@ -265,6 +595,14 @@ impl Array {
         }
     }
 
+    /// Returns a copy of the array type with a different length.
+    pub fn resized(&self, len: usize) -> Self {
+        Array {
+            elem_ty: self.elem_ty.clone(),
+            len,
+        }
+    }
+
     /// The size of the array in bytes.
     pub fn size(&self) -> usize {
         self.elem_ty.size() * self.len
@@ -295,6 +633,11 @@ impl Object {
         }
     }
 
+    /// The class name of the object.
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+
     /// The size of the object in bytes.
     pub fn size(&self) -> usize {
         self.fields.iter().map(|field| field.ty.size()).sum()
@@ -320,6 +663,27 @@ impl Object {
     pub fn fields(&self) -> impl Iterator<Item = &Field> {
         self.fields.iter()
     }
+
+    /// The number of fields in the object.
+    pub fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// The field at the given index, in declaration order. Returns `None` if the index is out of
+    /// bounds.
+    pub fn field_at(&self, index: usize) -> Option<&Field> {
+        self.fields.get(index)
+    }
+
+    /// The offset and type of the field with the given name. Returns `None` if the field does
+    /// not exist.
+    pub fn field_offset(&self, name: impl AsRef<str>) -> Option<(usize, &Type)> {
+        let name = name.as_ref();
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| (field.offset, &field.ty))
+    }
 }
 
 impl From<Primitive> for Type {
@@ -335,6 +699,22 @@ impl From<Primitive> for Type {
     }
 }
 
+impl TryFrom<&Type> for Primitive {
+    type Error = ();
+
+    fn try_from(ty: &Type) -> Result<Self, Self::Error> {
+        ty.as_primitive().ok_or(())
+    }
+}
+
+impl TryFrom<TypeRef<'_>> for Primitive {
+    type Error = ();
+
+    fn try_from(ty: TypeRef<'_>) -> Result<Self, Self::Error> {
+        ty.as_primitive().ok_or(())
+    }
+}
+
 impl From<Array> for Type {
     fn from(array: Array) -> Self {
         Type::Array(Box::new(array))
@@ -374,17 +754,22 @@ mod private {
 }
 
 /// Implemented for primitive types.
-pub trait IsPrimitive: private::Sealed {}
+pub trait IsPrimitive: private::Sealed {
+    /// The corresponding [`Primitive`].
+    const PRIMITIVE: Primitive;
+}
 
 macro_rules! impl_is_primitive {
-    ($($ty:ty),*) => {
+    ($($ty:ty => $primitive:ident),*) => {
         $(
-            impl IsPrimitive for $ty {}
+            impl IsPrimitive for $ty {
+                const PRIMITIVE: Primitive = Primitive::$primitive;
+            }
         )*
     };
 }
 
-impl_is_primitive!(bool, i32, i64, f32, f64);
+impl_is_primitive!(bool => Bool, i32 => Int32, i64 => Int64, f32 => Float32, f64 => Float64);
 
 /// Implemented for scalar types.
 pub trait IsScalar: IsPrimitive {}
@@ -405,3 +790,145 @@ pub trait IsFloatingPoint: private::Sealed {}
 impl IsFloatingPoint for f32 {}
 
 impl IsFloatingPoint for f64 {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty_arrays_are_serialised_without_groups() {
+        let empty = Array::new(Type::Float32, 0);
+        assert_eq!(TypeRef::Array(&empty).serialise_as_choc_type(), [7, 0]);
+
+        let resized = empty.resized(3);
+        assert_eq!(resized.len(), 3);
+        assert_eq!(resized.size(), 12);
+        assert_eq!(
+            TypeRef::Array(&resized).serialise_as_choc_type(),
+            [7, 1, 3, 3]
+        );
+        assert!(resized.resized(0).is_empty());
+    }
+
+    #[test]
+    fn packed_ints_round_trip() {
+        for value in [0, 1, 0x7F, 0x80, 300, u32::MAX as u64, u64::MAX] {
+            let mut buffer = vec![];
+            write_packed_int(&mut buffer, value);
+
+            let mut slice = buffer.as_slice();
+            assert_eq!(read_packed_int(&mut slice), Some(value));
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn overlong_packed_ints_are_rejected() {
+        let mut buffer = vec![];
+        write_packed_int(&mut buffer, u64::MAX);
+        assert_eq!(read_packed_int(&mut &buffer[..buffer.len() - 1]), None);
+
+        *buffer.last_mut().unwrap() = 0x02;
+        assert_eq!(read_packed_int(&mut buffer.as_slice()), None);
+
+        assert_eq!(read_packed_int(&mut [0xFF; 11].as_slice()), None);
+    }
+
+    #[test]
+    fn choc_types_round_trip() {
+        let ty: Type = Object::new("Voice")
+            .with_field("note", Type::Int32)
+            .with_field("levels", Array::new(Type::Float32, 4))
+            .with_field(
+                "nested",
+                Object::new("Inner")
+                    .with_field("on", Type::Bool)
+                    .with_field("name", Type::String),
+            )
+            .into();
+
+        let serialised = ty.as_ref().serialise_as_choc_type();
+        let mut buffer = serialised.as_slice();
+        assert_eq!(Type::deserialise_from_choc_type(&mut buffer), Some(ty));
+        assert!(buffer.is_empty());
+
+        assert_eq!(
+            Type::deserialise_from_choc_type(&mut [6, 2, 4].as_slice()),
+            Some(Array::new(Type::Float64, 2).into())
+        );
+        assert_eq!(
+            Type::deserialise_from_choc_type(&mut [7, 2, 1, 3, 2, 3].as_slice()),
+            Some(Array::new(Type::Float32, 3).into())
+        );
+        assert_eq!(
+            Type::deserialise_from_choc_type(&mut [7, 2, 1, 3, 1, 1].as_slice()),
+            None
+        );
+        assert_eq!(
+            Type::deserialise_from_choc_type(&mut [8, 1].as_slice()),
+            None
+        );
+    }
+
+    #[test]
+    fn structural_equality_ignores_class_names() {
+        let a = Object::new("a::Foo")
+            .with_field("x", Type::Int32)
+            .with_field("y", Type::Float64);
+        let b = Object::new("Foo")
+            .with_field("x", Type::Int32)
+            .with_field("y", Type::Float64);
+        let c = Object::new("Foo").with_field("x", Type::Int64);
+
+        assert_ne!(a, b);
+        assert!(TypeRef::Object(&a).structurally_eq(TypeRef::Object(&b)));
+        assert!(!TypeRef::Object(&a).structurally_eq(TypeRef::Object(&c)));
+
+        let swapped = Object::new("Foo")
+            .with_field("y", Type::Int32)
+            .with_field("x", Type::Float64);
+        assert!(!TypeRef::Object(&a).structurally_eq(TypeRef::Object(&swapped)));
+
+        let array_a = Array::new(a, 2);
+        let array_b = Array::new(b, 2);
+        assert!(TypeRef::Array(&array_a).structurally_eq(TypeRef::Array(&array_b)));
+        assert!(!TypeRef::Int32.structurally_eq(TypeRef::Int64));
+    }
+
+    #[test]
+    fn field_offsets_are_precomputed() {
+        let object = Object::new("Foo")
+            .with_field("a", Type::Int64)
+            .with_field("b", Type::Bool)
+            .with_field("c", Array::new(Type::Float32, 2));
+
+        assert_eq!(object.field_offset("a"), Some((0, &Type::Int64)));
+        assert_eq!(object.field_offset("b"), Some((8, &Type::Bool)));
+        assert_eq!(
+            object.field_offset("c"),
+            Some((12, &Type::from(Array::new(Type::Float32, 2))))
+        );
+        assert_eq!(object.field_offset("d"), None);
+    }
+
+    #[test]
+    fn type_predicates() {
+        let array = Type::from(Array::new(Type::Float32, 4));
+        let object = Type::from(Object::new("Foo").with_field("a", Type::Int32));
+
+        assert!(Type::Int32.is_primitive());
+        assert!(!Type::String.is_primitive());
+        assert!(array.is_array() && !array.is_primitive() && !array.is_object());
+        assert!(object.is_object() && !object.is_array());
+
+        assert_eq!(Primitive::try_from(&Type::Int64), Ok(Primitive::Int64));
+        assert_eq!(Primitive::try_from(&array), Err(()));
+        assert_eq!(object.as_ref().as_primitive(), None);
+        assert_eq!(TypeRef::Float64.as_primitive(), Some(Primitive::Float64));
+
+        assert_eq!(Type::Float32.element_count(), Some(1));
+        assert_eq!(array.element_count(), Some(4));
+        assert_eq!(Type::Void.element_count(), None);
+        assert_eq!(object.element_count(), None);
+    }
+}
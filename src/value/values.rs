@@ -1,8 +1,20 @@
 use {
-    crate::value::types::{Array, IsFloatingPoint, Object, Type, TypeRef},
+    crate::value::{
+        types::{
+            write_null_terminated_string, write_packed_int, Array, IsFloatingPoint, IsScalar,
+            Object, Type, TypeRef,
+        },
+        AlignedBytes,
+    },
     bytes::{Buf, BufMut},
     serde::{Deserialize, Serialize},
-    smallvec::SmallVec,
+    serde_json::Value as JsonValue,
+    std::{
+        borrow::Cow,
+        fmt::{self, Display, Formatter},
+        mem,
+        ops::Range,
+    },
 };
 
 /// A Cmajor value.
@@ -26,9 +38,16 @@ pub enum Value {
     /// A 64-bit floating-point value.
     Float64(f64),
 
-    /// A string value.
+    /// A string value, held by a performer and referenced by its handle.
     String(StringHandle),
 
+    /// A string value owned by the host.
+    ///
+    /// Text can be used to initialise external variables. It has no handle, so it can't be posted
+    /// to or set on an endpoint.
+    #[allow(clippy::box_collection)] // Boxed to keep `Value` at 16 bytes.
+    Text(Box<String>),
+
     /// An array value.
     Array(Box<ArrayValue>),
 
@@ -57,9 +76,12 @@ pub enum ValueRef<'a> {
     /// A 64-bit floating-point value.
     Float64(f64),
 
-    /// A string value.
+    /// A string value, held by a performer and referenced by its handle.
     String(StringHandle),
 
+    /// A string value owned by the host.
+    Text(&'a str),
+
     /// An array value.
     Array(ArrayValueRef<'a>),
 
@@ -75,7 +97,7 @@ pub struct StringHandle(pub(crate) u32);
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArrayValue {
     ty: Array,
-    data: SmallVec<[u8; 16]>,
+    data: AlignedBytes,
 }
 
 /// A reference to an [`ArrayValue`].
@@ -85,11 +107,21 @@ pub struct ArrayValueRef<'a> {
     data: &'a [u8],
 }
 
+/// A view over a contiguous range of an array's elements.
+///
+/// Returned by [`ArrayValueRef::get_range`]. It shares the original array's data, and only owns
+/// the type describing its (shorter) length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArraySlice<'a> {
+    ty: Array,
+    data: &'a [u8],
+}
+
 /// An object value.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ObjectValue {
     ty: Object,
-    data: SmallVec<[u8; 16]>,
+    data: AlignedBytes,
 }
 
 /// A reference to an [`ObjectValue`].
@@ -109,12 +141,19 @@ impl Value {
             Self::Int64(_) => TypeRef::Int64,
             Self::Float32(_) => TypeRef::Float32,
             Self::Float64(_) => TypeRef::Float64,
-            Self::String(_) => TypeRef::String,
+            Self::String(_) | Self::Text(_) => TypeRef::String,
             Self::Array(array) => TypeRef::Array(&array.ty),
             Self::Object(object) => TypeRef::Object(&object.ty),
         }
     }
 
+    /// The bytes of the value, as they are passed to and from a performer.
+    ///
+    /// See [`ValueRef::as_bytes`] for details of the layout.
+    pub fn as_bytes(&self) -> Cow<'_, [u8]> {
+        self.as_ref().as_bytes()
+    }
+
     /// Get a reference to the value.
     pub fn as_ref(&self) -> ValueRef<'_> {
         match self {
@@ -125,11 +164,40 @@ impl Value {
             Self::Float32(value) => ValueRef::Float32(*value),
             Self::Float64(value) => ValueRef::Float64(*value),
             Self::String(value) => ValueRef::String(*value),
+            Self::Text(text) => ValueRef::Text(text),
             Self::Array(ref array) => ValueRef::Array(array.as_ref().as_ref()),
             Self::Object(object) => ValueRef::Object(object.as_ref().as_ref()),
         }
     }
 
+    /// Construct a value of the given type from JSON.
+    ///
+    /// Numbers are coerced to the target primitive, arrays must have the same length as the
+    /// array type, and objects must have exactly the fields declared by the object type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::{json::json, value::{types::{Array, Type}, Value}};
+    /// let ty = Array::new(Type::Float32, 2).into();
+    /// let value = Value::from_json(&ty, &json!([1, 2.5])).unwrap();
+    /// assert_eq!(value, Value::from([1.0_f32, 2.5]));
+    /// ```
+    pub fn from_json(ty: &Type, json: &JsonValue) -> Result<Self, FromJsonError> {
+        let mut path = String::from("value");
+        match (ty, json) {
+            (Type::String, JsonValue::String(text)) => Ok(Self::from(text.as_str())),
+            _ => value_from_json(ty.as_ref(), json, &mut path),
+        }
+    }
+
+    /// Convert the value into JSON.
+    ///
+    /// See [`ValueRef::to_json`] for details.
+    pub fn to_json(&self) -> JsonValue {
+        self.as_ref().to_json()
+    }
+
     pub(crate) fn with_bytes<R>(&self, callback: impl FnMut(&[u8]) -> R) -> R {
         self.as_ref().with_bytes(callback)
     }
@@ -137,6 +205,16 @@ impl Value {
     pub(crate) fn serialise_as_choc_value(&self) -> Vec<u8> {
         let mut serialised = Vec::new();
         serialised.put_slice(self.ty().serialise_as_choc_type().as_slice());
+
+        if let Self::Text(text) = self {
+            // Strings are serialised as a handle into a dictionary that follows the value data.
+            // Handles are offsets into the dictionary, plus one.
+            serialised.put_u32_ne(1);
+            write_packed_int(&mut serialised, text.len() as u64 + 1);
+            write_null_terminated_string(&mut serialised, text.as_str());
+            return serialised;
+        }
+
         self.with_bytes(|bytes| {
             serialised.put_slice(bytes);
         });
@@ -162,6 +240,14 @@ impl<'a> ValueRef<'a> {
         }
     }
 
+    /// Interpret bytes as a value of the given type, such as those copied by
+    /// [`Performer::copy_output_into`](crate::performer::Performer::copy_output_into).
+    ///
+    /// Returns `None` if there are fewer bytes than the size of the type.
+    pub fn from_bytes(ty: TypeRef<'a>, data: &'a [u8]) -> Option<Self> {
+        (data.len() >= ty.size()).then(|| Self::new_from_slice(ty, data))
+    }
+
     /// If the value is an array, get a reference to it. Otherwise returns `None`.
     pub fn as_array(&self) -> Option<ArrayValueRef<'_>> {
         match self {
@@ -178,6 +264,67 @@ impl<'a> ValueRef<'a> {
         }
     }
 
+    /// If the value is a bool, get it. Otherwise, returns `None`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Self::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// If the value is an `int32`, get it. Otherwise, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::ValueRef;
+    /// let value = ValueRef::Int32(42);
+    /// assert_eq!(value.as_i32(), Some(42));
+    /// assert_eq!(value.as_f32(), None);
+    /// ```
+    ///
+    /// This is handy for reading from an endpoint of a type only known at runtime:
+    ///
+    /// ```no_run
+    /// # use cmajor::{performer::{Endpoint, OutputValue, Performer}, value::Value};
+    /// # fn example(performer: &mut Performer, endpoint: Endpoint<OutputValue<Value>>) {
+    /// let gain = performer
+    ///     .get(endpoint)
+    ///     .ok()
+    ///     .and_then(|value| value.as_f32());
+    /// # }
+    /// ```
+    pub fn as_i32(&self) -> Option<i32> {
+        match *self {
+            Self::Int32(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// If the value is an `int64`, get it. Otherwise, returns `None`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::Int64(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// If the value is a `float32`, get it. Otherwise, returns `None`.
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            Self::Float32(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// If the value is a `float64`, get it. Otherwise, returns `None`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Float64(value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Get the type of the value.
     pub fn ty(&self) -> TypeRef<'_> {
         match self {
@@ -187,12 +334,85 @@ impl<'a> ValueRef<'a> {
             Self::Int64(_) => TypeRef::Int64,
             Self::Float32(_) => TypeRef::Float32,
             Self::Float64(_) => TypeRef::Float64,
-            Self::String(_) => TypeRef::String,
+            Self::String(_) | Self::Text(_) => TypeRef::String,
             Self::Array(array) => TypeRef::Array(array.ty),
             Self::Object(object) => TypeRef::Object(object.ty),
         }
     }
 
+    /// The name of the value's type as it would be written in Cmajor.
+    ///
+    /// See [`TypeRef::type_name`].
+    pub fn type_name(&self) -> String {
+        self.ty().type_name()
+    }
+
+    /// Returns `true` if the values have the same shape and data, ignoring the class names of
+    /// objects.
+    ///
+    /// Use this instead of `==` when comparing objects that may have been created with different
+    /// class names, such as a struct built on the host against one read back from a performer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::{ObjectValue, ValueRef};
+    /// let a = ObjectValue::builder("Foo").field("x", 1).build().unwrap();
+    /// let b = ObjectValue::builder("my_crate::Foo")
+    ///     .field("x", 1)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_ne!(ValueRef::from(&a), ValueRef::from(&b));
+    /// assert!(ValueRef::from(&a).structurally_eq(&ValueRef::from(&b)));
+    /// ```
+    pub fn structurally_eq(&self, other: &ValueRef<'_>) -> bool {
+        match (self, other) {
+            (Self::Array(a), ValueRef::Array(b)) => {
+                TypeRef::Array(a.ty).structurally_eq(TypeRef::Array(b.ty))
+                    && a.elems().zip(b.elems()).all(|(a, b)| a.structurally_eq(&b))
+            }
+            (Self::Object(a), ValueRef::Object(b)) => {
+                TypeRef::Object(a.ty).structurally_eq(TypeRef::Object(b.ty))
+                    && a.fields()
+                        .zip(b.fields())
+                        .all(|((_, a), (_, b))| a.structurally_eq(&b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Convert the value into JSON.
+    ///
+    /// Arrays become JSON arrays, objects become JSON maps keyed by field name, and primitives
+    /// become JSON scalars. Void and non-finite floats become `null`, and unresolved string
+    /// handles become their numeric handle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::Value;
+    /// let value: Value = [1.5_f32, 2.5].into();
+    /// assert_eq!(value.to_json(), cmajor::json::json!([1.5, 2.5]));
+    /// ```
+    pub fn to_json(&self) -> JsonValue {
+        match *self {
+            Self::Void => JsonValue::Null,
+            Self::Bool(value) => value.into(),
+            Self::Int32(value) => value.into(),
+            Self::Int64(value) => value.into(),
+            Self::Float32(value) => value.into(),
+            Self::Float64(value) => value.into(),
+            Self::String(StringHandle(handle)) => handle.into(),
+            Self::Text(text) => text.into(),
+            Self::Array(array) => array.elems().map(|elem| elem.to_json()).collect(),
+            Self::Object(object) => object
+                .fields()
+                .map(|(name, value)| (name.to_owned(), value.to_json()))
+                .collect(),
+        }
+    }
+
     /// Clone the value into an owned [`Value`].
     pub fn to_owned(&self) -> Value {
         match *self {
@@ -203,11 +423,38 @@ impl<'a> ValueRef<'a> {
             Self::Float32(value) => Value::from(value),
             Self::Float64(value) => Value::from(value),
             Self::String(value) => Value::String(value),
+            Self::Text(text) => Value::from(text),
             Self::Array(array) => Value::from(array.to_owned()),
             Self::Object(object) => Value::from(object.to_owned()),
         }
     }
 
+    /// The bytes of the value, as they are passed to and from a performer.
+    ///
+    /// Values are laid out in native-endian order. Booleans and string handles take four bytes,
+    /// and arrays and objects are packed without padding. Arrays and objects are borrowed, while
+    /// primitives are copied into a small owned buffer. Host-owned text has no representation in
+    /// the performer, and is written as a null string handle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::{Value, ValueRef};
+    /// assert_eq!(ValueRef::Int32(1).as_bytes().as_ref(), 1_i32.to_ne_bytes());
+    ///
+    /// let value = Value::from([1_i32, 2]);
+    /// let mut expected = 1_i32.to_ne_bytes().to_vec();
+    /// expected.extend(2_i32.to_ne_bytes());
+    /// assert_eq!(value.as_bytes().as_ref(), expected);
+    /// ```
+    pub fn as_bytes(&self) -> Cow<'a, [u8]> {
+        match *self {
+            Self::Array(array) => Cow::Borrowed(array.data),
+            Self::Object(object) => Cow::Borrowed(object.data),
+            value => value.with_bytes(|bytes| Cow::Owned(bytes.to_vec())),
+        }
+    }
+
     pub(crate) fn with_bytes<R>(&self, mut callback: impl FnMut(&[u8]) -> R) -> R {
         match *self {
             Self::Void => callback(&[]),
@@ -217,6 +464,7 @@ impl<'a> ValueRef<'a> {
             Self::Float32(value) => callback(value.to_ne_bytes().as_slice()),
             Self::Float64(value) => callback(value.to_ne_bytes().as_slice()),
             Self::String(StringHandle(value)) => callback(value.to_ne_bytes().as_slice()),
+            Self::Text(_) => callback(0_u32.to_ne_bytes().as_slice()),
             Self::Array(array) => callback(array.data),
             Self::Object(object) => callback(object.data),
         }
@@ -224,6 +472,77 @@ impl<'a> ValueRef<'a> {
 }
 
 impl ArrayValue {
+    /// Create an array from a slice of elements, whose length may only be known at runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the elements don't all have the same type. See [`ArrayValue::try_from_iter`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::{ArrayValue, ValueRef};
+    /// let samples = vec![0.25_f32; 64];
+    /// let array = ArrayValue::from_slice(&samples);
+    ///
+    /// assert_eq!(array.as_ref().len(), 64);
+    /// assert_eq!(array.as_ref().get(0), Some(ValueRef::Float32(0.25)));
+    /// ```
+    pub fn from_slice<T>(elems: &[T]) -> Self
+    where
+        T: Into<Value> + Default + Clone,
+    {
+        elems.iter().cloned().collect()
+    }
+
+    /// Create an array from an iterator of elements.
+    ///
+    /// The element type is taken from the first element, or from `T::default()` if there are
+    /// none. Returns an error if a later element has a different type (such as an inner array of
+    /// a different length), or if any element is [`Value::Text`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::{ArrayValue, ArrayValueError};
+    /// let rows = vec![vec![1, 2], vec![3, 4]];
+    /// assert!(ArrayValue::try_from_iter(rows).is_ok());
+    ///
+    /// let ragged = vec![vec![1, 2], vec![3]];
+    /// assert_eq!(
+    ///     ArrayValue::try_from_iter(ragged),
+    ///     Err(ArrayValueError::MismatchedElementType(1))
+    /// );
+    /// ```
+    pub fn try_from_iter<T>(iter: impl IntoIterator<Item = T>) -> Result<Self, ArrayValueError>
+    where
+        T: Into<Value> + Default,
+    {
+        let mut iter = iter.into_iter().map(Into::into).peekable();
+        let elem_ty = match iter.peek() {
+            Some(first) => first.ty().to_owned(),
+            None => T::default().into().ty().to_owned(),
+        };
+
+        let mut len = 0;
+        let mut data = AlignedBytes::new();
+        for (index, value) in iter.enumerate() {
+            if let Value::Text(_) = value {
+                return Err(ArrayValueError::UnsupportedElementType(index));
+            }
+            if value.ty() != elem_ty.as_ref() {
+                return Err(ArrayValueError::MismatchedElementType(index));
+            }
+            value.with_bytes(|bytes| data.extend_from_slice(bytes));
+            len += 1;
+        }
+
+        Ok(ArrayValue {
+            ty: Array::new(elem_ty, len),
+            data,
+        })
+    }
+
     /// Get a reference to the array.
     pub fn as_ref(&self) -> ArrayValueRef<'_> {
         ArrayValueRef {
@@ -267,6 +586,72 @@ impl<'a> ArrayValueRef<'a> {
         Some(ValueRef::new_from_slice(ty.as_ref(), data))
     }
 
+    /// Borrow the array's elements as a slice of scalars, without copying.
+    ///
+    /// Returns `None` if the element type isn't `T`. Value data is stored aligned for every
+    /// scalar type, so this always succeeds for arrays, and elements of arrays, that own their
+    /// data or are read from a performer. The one exception is an array held in a field of an
+    /// object: fields are packed without padding, so if the field's offset isn't a multiple of
+    /// the alignment of `T` this also returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::ArrayValue;
+    /// let array: ArrayValue = [1, 2, 3].into();
+    ///
+    /// assert_eq!(array.as_ref().as_slice::<i32>(), Some([1, 2, 3].as_slice()));
+    /// assert_eq!(array.as_ref().as_slice::<f32>(), None);
+    /// ```
+    pub fn as_slice<T>(&self) -> Option<&'a [T]>
+    where
+        T: IsScalar,
+    {
+        if self.elem_ty().as_primitive() != Some(T::PRIMITIVE) {
+            return None;
+        }
+
+        if self.data.as_ptr().align_offset(mem::align_of::<T>()) != 0 {
+            return None;
+        }
+
+        debug_assert_eq!(self.data.len(), self.len() * mem::size_of::<T>());
+
+        // SAFETY: the elements are scalars of type `T`, stored contiguously in native-endian
+        // order, and the data has been checked to be aligned for `T`.
+        Some(unsafe { std::slice::from_raw_parts(self.data.as_ptr().cast(), self.len()) })
+    }
+
+    /// Get a view over the elements in the given range. Returns `None` if the range is out of
+    /// bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::{ArrayValue, ValueRef};
+    /// let array: ArrayValue = [1, 2, 3, 4, 5].into();
+    /// let array_ref = array.as_ref();
+    ///
+    /// let slice = array_ref.get_range(1..4).unwrap();
+    /// let slice = slice.as_ref();
+    ///
+    /// assert_eq!(slice.len(), 3);
+    /// assert_eq!(slice.get(0), Some(ValueRef::Int32(2)));
+    /// assert_eq!(slice.get(2), Some(ValueRef::Int32(4)));
+    /// assert!(array_ref.get_range(3..6).is_none());
+    /// ```
+    pub fn get_range(&self, range: Range<usize>) -> Option<ArraySlice<'a>> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+
+        let elem_size = self.elem_ty().size();
+        Some(ArraySlice {
+            ty: Array::new(self.elem_ty().clone(), range.len()),
+            data: &self.data[range.start * elem_size..range.end * elem_size],
+        })
+    }
+
     /// Returns an iterator over the array's elements.
     ///
     /// # Example
@@ -322,7 +707,17 @@ impl<'a> ArrayValueRef<'a> {
     pub fn to_owned(&self) -> ArrayValue {
         ArrayValue {
             ty: self.ty.clone(),
-            data: SmallVec::from_slice(self.data),
+            data: AlignedBytes::from_slice(self.data),
+        }
+    }
+}
+
+impl ArraySlice<'_> {
+    /// Get a reference to the elements in the slice.
+    pub fn as_ref(&self) -> ArrayValueRef<'_> {
+        ArrayValueRef {
+            ty: &self.ty,
+            data: self.data,
         }
     }
 }
@@ -337,6 +732,135 @@ impl ObjectValue {
     }
 }
 
+impl ObjectValue {
+    /// Create a builder for an object value of the given class.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::{ObjectValue, Value, ValueRef};
+    /// let object = ObjectValue::builder("S")
+    ///     .field("a", true)
+    ///     .field("b", 7.0_f32)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(object.as_ref().field("b"), Some(ValueRef::Float32(7.0)));
+    /// ```
+    pub fn builder(class: impl AsRef<str>) -> ObjectValueBuilder {
+        ObjectValueBuilder {
+            ty: Object::new(class),
+            expected: None,
+            data: AlignedBytes::new(),
+            error: None,
+        }
+    }
+
+    /// Create a builder for an object value of the given type.
+    ///
+    /// Fields must be added in the order they're declared in the type, with matching types.
+    pub fn builder_for(ty: &Object) -> ObjectValueBuilder {
+        ObjectValueBuilder {
+            expected: Some(ty.clone()),
+            ..Self::builder(ty.class())
+        }
+    }
+}
+
+/// An error that can occur when constructing an [`ArrayValue`] from its elements.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ArrayValueError {
+    /// The element at this index has a different type to the first element.
+    #[error("element {0} has a different type to the first element")]
+    MismatchedElementType(usize),
+
+    /// The element at this index has a type that can't be stored in an array.
+    #[error("element {0} has a type that can't be stored in an array")]
+    UnsupportedElementType(usize),
+}
+
+/// A builder for an [`ObjectValue`].
+#[derive(Debug)]
+pub struct ObjectValueBuilder {
+    ty: Object,
+    expected: Option<Object>,
+    data: AlignedBytes,
+    error: Option<ObjectValueError>,
+}
+
+/// An error that can occur when building an [`ObjectValue`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ObjectValueError {
+    /// A field with the same name has already been added.
+    #[error("duplicate field `{0}`")]
+    DuplicateField(String),
+
+    /// The field's value can't be serialised into the object.
+    #[error("field `{0}` has a type that can't be serialised")]
+    UnsupportedFieldType(String),
+
+    /// The field doesn't match the next field declared by the object's type.
+    #[error("unexpected field `{0}`")]
+    UnexpectedField(String),
+
+    /// A field declared by the object's type wasn't added.
+    #[error("missing field `{0}`")]
+    MissingField(String),
+}
+
+impl ObjectValueBuilder {
+    /// Add a field to the object.
+    pub fn field(mut self, name: impl AsRef<str>, value: impl Into<Value>) -> Self {
+        if self.error.is_none() {
+            if let Err(error) = self.add_field(name.as_ref(), value.into()) {
+                self.error = Some(error);
+            }
+        }
+        self
+    }
+
+    fn add_field(&mut self, name: &str, value: Value) -> Result<(), ObjectValueError> {
+        if self.ty.fields().any(|field| field.name() == name) {
+            return Err(ObjectValueError::DuplicateField(name.to_owned()));
+        }
+
+        if let Value::Text(_) = value {
+            return Err(ObjectValueError::UnsupportedFieldType(name.to_owned()));
+        }
+
+        if let Some(expected) = &self.expected {
+            let index = self.ty.fields().count();
+            match expected.fields().nth(index) {
+                Some(field) if field.name() == name && field.ty().as_ref() == value.ty() => {}
+                _ => return Err(ObjectValueError::UnexpectedField(name.to_owned())),
+            }
+        }
+
+        self.ty.add_field(name, value.ty().to_owned());
+        value.with_bytes(|bytes| self.data.extend_from_slice(bytes));
+
+        Ok(())
+    }
+
+    /// Build the object value.
+    pub fn build(self) -> Result<ObjectValue, ObjectValueError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        if let Some(expected) = &self.expected {
+            if let Some(missing) = expected.fields().nth(self.ty.fields().count()) {
+                return Err(ObjectValueError::MissingField(missing.name().to_owned()));
+            }
+        }
+
+        Ok(ObjectValue {
+            ty: self.ty,
+            data: self.data,
+        })
+    }
+}
+
 impl<'a> ObjectValueRef<'a> {
     pub(crate) fn new_from_slice<'b>(ty: &'b Object, data: &'b [u8]) -> ObjectValueRef<'a>
     where
@@ -350,38 +874,284 @@ impl<'a> ObjectValueRef<'a> {
 
     /// Get the value of the given field. Returns `None` if the field does not exist.
     pub fn field(&self, name: impl AsRef<str>) -> Option<ValueRef<'_>> {
-        let mut offset = 0;
         self.ty
-            .fields()
-            .find_map(|field| {
-                (field.name() == name.as_ref())
-                    .then_some((field, offset))
-                    .or_else(|| {
-                        offset += field.ty().size();
-                        None
-                    })
-            })
-            .map(|(field, offset)| {
-                ValueRef::new_from_slice(field.ty().as_ref(), &self.data[offset..])
-            })
+            .field_offset(name)
+            .map(|(offset, ty)| ValueRef::new_from_slice(ty.as_ref(), &self.data[offset..]))
+    }
+
+    /// Get the name and value of the field at the given index, in declaration order. Returns
+    /// `None` if the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cmajor::value::{ObjectValue, ValueRef};
+    /// let object = ObjectValue::builder("Pair")
+    ///     .field("first", 1)
+    ///     .field("second", 2.5_f32)
+    ///     .build()
+    ///     .unwrap();
+    /// let object = object.as_ref();
+    ///
+    /// assert_eq!(object.field_count(), 2);
+    /// assert_eq!(object.field_at(1), Some(("second", ValueRef::Float32(2.5))));
+    /// assert_eq!(object.field_at(2), None);
+    /// ```
+    pub fn field_at(&self, index: usize) -> Option<(&str, ValueRef<'_>)> {
+        self.ty.field_at(index).map(|field| {
+            let value = ValueRef::new_from_slice(field.ty().as_ref(), &self.data[field.offset()..]);
+            (field.name(), value)
+        })
+    }
+
+    /// The number of fields in the object.
+    pub fn field_count(&self) -> usize {
+        self.ty.field_count()
     }
 
     /// Returns an iterator over the object's fields.
     pub fn fields(&self) -> impl Iterator<Item = (&str, ValueRef<'_>)> + '_ {
-        self.ty
-            .fields()
-            .filter_map(|field| self.field(field.name()).map(|value| (field.name(), value)))
+        self.ty.fields().map(|field| {
+            let value = ValueRef::new_from_slice(field.ty().as_ref(), &self.data[field.offset()..]);
+            (field.name(), value)
+        })
     }
 
     /// Clone into an owned [`ObjectValue`].
     pub fn to_owned(&self) -> ObjectValue {
         ObjectValue {
             ty: self.ty.clone(),
-            data: SmallVec::from_slice(self.data),
+            data: AlignedBytes::from_slice(self.data),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+/// Formats the value using Cmajor literal syntax.
+///
+/// # Example
+///
+/// ```
+/// # use cmajor::value::Value;
+/// let value: Value = [1, 2, 3].into();
+/// assert_eq!(value.to_string(), "int32[] (1, 2, 3)");
+/// ```
+impl Display for ValueRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Void => f.write_str("void"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int32(value) => write!(f, "{value}"),
+            Self::Int64(value) => write!(f, "{value}_i64"),
+            Self::Float32(value) => write!(f, "{value:?}f"),
+            Self::Float64(value) => write!(f, "{value:?}"),
+            Self::String(StringHandle(handle)) => write!(f, "<string {handle}>"),
+            Self::Text(text) => write!(f, "{text:?}"),
+            Self::Array(array) => array.fmt(f),
+            Self::Object(object) => object.fmt(f),
+        }
+    }
+}
+
+impl Display for ArrayValueRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.elem_ty().as_ref().type_name())?;
+        f.write_str("[] (")?;
+        for (index, elem) in self.elems().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            elem.fmt(f)?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl Display for ObjectValueRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (", self.ty.class())?;
+        for (index, (name, value)) in self.fields().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{name}: {value}")?;
         }
+        f.write_str(")")
     }
 }
 
+/// An error that can occur when constructing a [`Value`] from JSON.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FromJsonError {
+    /// The JSON doesn't have the shape of the expected type.
+    #[error("expected {expected} at `{path}`")]
+    TypeMismatch {
+        /// The location of the mismatch.
+        path: String,
+        /// The name of the expected type.
+        expected: String,
+    },
+
+    /// A number doesn't fit in the expected type.
+    #[error("number out of range for {expected} at `{path}`")]
+    OutOfRange {
+        /// The location of the number.
+        path: String,
+        /// The name of the expected type.
+        expected: String,
+    },
+
+    /// An array has the wrong number of elements.
+    #[error("expected {expected} elements at `{path}`, found {found}")]
+    LengthMismatch {
+        /// The location of the array.
+        path: String,
+        /// The expected number of elements.
+        expected: usize,
+        /// The number of elements found.
+        found: usize,
+    },
+
+    /// An object is missing a field.
+    #[error("missing field `{field}` at `{path}`")]
+    MissingField {
+        /// The location of the object.
+        path: String,
+        /// The name of the missing field.
+        field: String,
+    },
+
+    /// An object has a field that isn't declared by its type.
+    #[error("unexpected field `{field}` at `{path}`")]
+    UnexpectedField {
+        /// The location of the object.
+        path: String,
+        /// The name of the unexpected field.
+        field: String,
+    },
+
+    /// Strings can only be converted at the top level, as they have no handle to embed.
+    #[error("unsupported string at `{path}`")]
+    UnsupportedString {
+        /// The location of the string.
+        path: String,
+    },
+}
+
+fn value_from_json(
+    ty: TypeRef<'_>,
+    json: &JsonValue,
+    path: &mut String,
+) -> Result<Value, FromJsonError> {
+    let type_mismatch = |path: &str| FromJsonError::TypeMismatch {
+        path: path.to_owned(),
+        expected: ty.type_name(),
+    };
+    let out_of_range = |path: &str| FromJsonError::OutOfRange {
+        path: path.to_owned(),
+        expected: ty.type_name(),
+    };
+
+    let integer = |json: &JsonValue| {
+        json.as_i64().or_else(|| {
+            json.as_f64()
+                .filter(|value| value.fract() == 0.0)
+                .map(|value| value as i64)
+        })
+    };
+
+    Ok(match ty {
+        TypeRef::Void => match json {
+            JsonValue::Null => Value::Void,
+            _ => return Err(type_mismatch(path)),
+        },
+        TypeRef::Bool => Value::Bool(json.as_bool().ok_or_else(|| type_mismatch(path))?),
+        TypeRef::Int32 => {
+            let value = integer(json).ok_or_else(|| type_mismatch(path))?;
+            Value::Int32(i32::try_from(value).map_err(|_| out_of_range(path))?)
+        }
+        TypeRef::Int64 => Value::Int64(integer(json).ok_or_else(|| type_mismatch(path))?),
+        TypeRef::Float32 => {
+            Value::Float32(json.as_f64().ok_or_else(|| type_mismatch(path))? as f32)
+        }
+        TypeRef::Float64 => Value::Float64(json.as_f64().ok_or_else(|| type_mismatch(path))?),
+        TypeRef::String => {
+            return Err(match json {
+                JsonValue::String(_) => FromJsonError::UnsupportedString {
+                    path: path.to_owned(),
+                },
+                _ => type_mismatch(path),
+            })
+        }
+        TypeRef::Array(array) => {
+            let elems = json.as_array().ok_or_else(|| type_mismatch(path))?;
+            if elems.len() != array.len() {
+                return Err(FromJsonError::LengthMismatch {
+                    path: path.to_owned(),
+                    expected: array.len(),
+                    found: elems.len(),
+                });
+            }
+
+            let mut data = AlignedBytes::new();
+            for (index, elem) in elems.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{index}]"));
+                let elem = value_from_json(array.elem_ty().as_ref(), elem, path)?;
+                path.truncate(len);
+
+                elem.with_bytes(|bytes| data.extend_from_slice(bytes));
+            }
+
+            Value::Array(Box::new(ArrayValue {
+                ty: array.clone(),
+                data,
+            }))
+        }
+        TypeRef::Object(object) => {
+            let fields = json.as_object().ok_or_else(|| type_mismatch(path))?;
+            if let Some(field) = fields
+                .keys()
+                .find(|name| object.fields().all(|field| field.name() != *name))
+            {
+                return Err(FromJsonError::UnexpectedField {
+                    path: path.to_owned(),
+                    field: field.clone(),
+                });
+            }
+
+            let mut data = AlignedBytes::new();
+            for field in object.fields() {
+                let value =
+                    fields
+                        .get(field.name())
+                        .ok_or_else(|| FromJsonError::MissingField {
+                            path: path.to_owned(),
+                            field: field.name().to_owned(),
+                        })?;
+
+                let len = path.len();
+                path.push('.');
+                path.push_str(field.name());
+                let value = value_from_json(field.ty().as_ref(), value, path)?;
+                path.truncate(len);
+
+                value.with_bytes(|bytes| data.extend_from_slice(bytes));
+            }
+
+            Value::Object(Box::new(ObjectValue {
+                ty: object.clone(),
+                data,
+            }))
+        }
+    })
+}
+
 impl From<()> for Value {
     fn from(_: ()) -> Self {
         Self::Void
@@ -454,6 +1224,24 @@ impl From<f64> for ValueRef<'_> {
     }
 }
 
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Self::Text(Box::new(text.to_owned()))
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Self::Text(Box::new(text))
+    }
+}
+
+impl<'a> From<&'a str> for ValueRef<'a> {
+    fn from(text: &'a str) -> Self {
+        Self::Text(text)
+    }
+}
+
 impl From<ArrayValue> for Value {
     fn from(array: ArrayValue) -> Self {
         Self::Array(Box::new(array))
@@ -500,7 +1288,7 @@ impl From<Complex32> for ObjectValue {
             .with_field("real", Type::Float32)
             .with_field("imag", Type::Float32);
 
-        let mut data = SmallVec::new();
+        let mut data = AlignedBytes::new();
         data.extend_from_slice(&real.to_ne_bytes());
         data.extend_from_slice(&imag.to_ne_bytes());
 
@@ -536,7 +1324,7 @@ impl From<Complex64> for ObjectValue {
             .with_field("real", Type::Float64)
             .with_field("imag", Type::Float64);
 
-        let mut data = SmallVec::new();
+        let mut data = AlignedBytes::new();
         data.extend_from_slice(&real.to_ne_bytes());
         data.extend_from_slice(&imag.to_ne_bytes());
 
@@ -571,18 +1359,34 @@ where
     T: Into<Value> + Default,
 {
     fn from(value: [T; N]) -> Self {
-        let v = T::default().into();
-        let ty = v.ty();
+        value.into_iter().collect()
+    }
+}
+
+impl<T> From<Vec<T>> for ArrayValue
+where
+    T: Into<Value> + Default,
+{
+    fn from(value: Vec<T>) -> Self {
+        value.into_iter().collect()
+    }
+}
 
-        let array = Array::new(ty.to_owned(), N);
-        let mut data = SmallVec::new();
-        for value in value {
-            let value: Value = value.into();
-            value.with_bytes(|bytes| {
-                data.extend_from_slice(bytes);
-            });
+impl<T> FromIterator<T> for ArrayValue
+where
+    T: Into<Value> + Default,
+{
+    /// Collect elements into an array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the elements don't all have the same type, or if any element is
+    /// [`Value::Text`]. See [`ArrayValue::try_from_iter`] for a fallible version.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        match Self::try_from_iter(iter) {
+            Ok(array) => array,
+            Err(error) => panic!("invalid array: {error}"),
         }
-        ArrayValue { ty: array, data }
     }
 }
 
@@ -595,6 +1399,68 @@ where
     }
 }
 
+impl<T> From<Vec<T>> for Value
+where
+    T: Into<Value> + Default,
+{
+    fn from(value: Vec<T>) -> Self {
+        ArrayValue::from(value).into()
+    }
+}
+
+impl<T> TryFrom<ArrayValueRef<'_>> for Vec<T>
+where
+    T: for<'a> TryFrom<ValueRef<'a>>,
+{
+    type Error = ();
+
+    fn try_from(array: ArrayValueRef<'_>) -> Result<Self, Self::Error> {
+        array
+            .elems()
+            .map(|elem| T::try_from(elem).map_err(|_| ()))
+            .collect()
+    }
+}
+
+impl<T, const N: usize> TryFrom<ArrayValueRef<'_>> for [T; N]
+where
+    T: for<'a> TryFrom<ValueRef<'a>>,
+{
+    type Error = ();
+
+    fn try_from(array: ArrayValueRef<'_>) -> Result<Self, Self::Error> {
+        Vec::try_from(array)?.try_into().map_err(|_| ())
+    }
+}
+
+impl<T> TryFrom<ValueRef<'_>> for Vec<T>
+where
+    T: for<'a> TryFrom<ValueRef<'a>>,
+{
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
+        match value {
+            ValueRef::Array(array) => array.try_into(),
+            _ => Err(()),
+        }
+    }
+}
+
+impl<T, const N: usize> TryFrom<ValueRef<'_>> for [T; N]
+where
+    T: for<'a> TryFrom<ValueRef<'a>>,
+{
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
+        match value {
+            ValueRef::Array(array) => array.try_into(),
+            _ => Err(()),
+        }
+    }
+}
+
 impl<'a> From<&'a Value> for ValueRef<'a> {
     fn from(value: &'a Value) -> Self {
         match value {
@@ -605,12 +1471,27 @@ impl<'a> From<&'a Value> for ValueRef<'a> {
             Value::Float32(value) => Self::Float32(*value),
             Value::Float64(value) => Self::Float64(*value),
             Value::String(value) => Self::String(*value),
+            Value::Text(text) => Self::Text(text),
             Value::Array(array) => Self::Array(array.as_ref().as_ref()),
             Value::Object(object) => Self::Object(object.as_ref().as_ref()),
         }
     }
 }
 
+macro_rules! impl_partial_eq_for_value_ref {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl PartialEq<$ty> for ValueRef<'_> {
+                fn eq(&self, other: &$ty) -> bool {
+                    matches!(self, ValueRef::$variant(value) if value == other)
+                }
+            }
+        )*
+    };
+}
+
+impl_partial_eq_for_value_ref!(bool => Bool, i32 => Int32, i64 => Int64, f32 => Float32, f64 => Float64);
+
 impl TryFrom<ValueRef<'_>> for bool {
     type Error = ();
 
@@ -722,6 +1603,37 @@ mod test {
         assert_eq!(array_view.get(2), Some(ValueRef::Int32(7)));
     }
 
+    #[test]
+    fn array_from_slice_matches_array_literal() {
+        let from_literal = ArrayValue::from([5, 6, 7]);
+        let from_slice = ArrayValue::from_slice(vec![5, 6, 7].as_slice());
+
+        assert_eq!(from_literal, from_slice);
+    }
+
+    #[test]
+    fn array_element_type_is_taken_from_the_elements() {
+        let nested = ArrayValue::from(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(nested, ArrayValue::from([[1, 2, 3], [4, 5, 6]]));
+
+        let nested = nested.as_ref();
+        assert_eq!(nested.elem_ty(), &Type::from(Array::new(Type::Int32, 3)));
+        let row = nested.get(1).unwrap();
+        assert_eq!(row.as_array().unwrap().get(2), Some(ValueRef::Int32(6)));
+
+        let empty = ArrayValue::from(Vec::<Vec<i32>>::new());
+        assert_eq!(empty.as_ref().len(), 0);
+
+        assert_eq!(
+            ArrayValue::try_from_iter(vec![vec![1, 2], vec![3]]),
+            Err(ArrayValueError::MismatchedElementType(1))
+        );
+        assert_eq!(
+            ArrayValue::try_from_iter(vec![String::from("a")]),
+            Err(ArrayValueError::UnsupportedElementType(0))
+        );
+    }
+
     #[test]
     fn multi_dimensional_array_as_value() {
         let array: Type = Array::new(Array::new(Type::Int32, 3), 2).into();
@@ -783,6 +1695,309 @@ mod test {
         assert_eq!(inner.field("d"), Some(ValueRef::Bool(true)));
     }
 
+    #[test]
+    fn text_is_serialised_with_a_string_dictionary() {
+        let value = Value::from("hi");
+
+        assert_eq!(
+            value.serialise_as_choc_value(),
+            [&[9][..], &1_u32.to_ne_bytes(), &[3, b'h', b'i', 0]].concat()
+        );
+    }
+
+    #[test]
+    fn build_an_object_value() {
+        let object = ObjectValue::builder("test")
+            .field("a", 5_i32)
+            .field("b", 53_i64)
+            .field(
+                "c",
+                ObjectValue::builder("inner")
+                    .field("d", true)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let ty = Object::new("test")
+            .with_field("a", Type::Int32)
+            .with_field("b", Type::Int64)
+            .with_field("c", Object::new("inner").with_field("d", Type::Bool));
+
+        let object = object.as_ref();
+        assert_eq!(object.ty, &ty);
+        assert_eq!(object.field("b"), Some(ValueRef::Int64(53)));
+    }
+
+    #[test]
+    fn building_an_object_value_validates_fields() {
+        let ty = Object::new("S")
+            .with_field("a", Type::Bool)
+            .with_field("b", Type::Float32);
+
+        assert!(ObjectValue::builder_for(&ty)
+            .field("a", true)
+            .field("b", 7.0_f32)
+            .build()
+            .is_ok());
+
+        assert_eq!(
+            ObjectValue::builder_for(&ty)
+                .field("b", 7.0_f32)
+                .field("a", true)
+                .build(),
+            Err(ObjectValueError::UnexpectedField("b".to_owned()))
+        );
+
+        assert_eq!(
+            ObjectValue::builder_for(&ty).field("a", true).build(),
+            Err(ObjectValueError::MissingField("b".to_owned()))
+        );
+
+        assert_eq!(
+            ObjectValue::builder("S")
+                .field("a", 1)
+                .field("a", 2)
+                .build(),
+            Err(ObjectValueError::DuplicateField("a".to_owned()))
+        );
+
+        assert_eq!(
+            ObjectValue::builder("S").field("a", "text").build(),
+            Err(ObjectValueError::UnsupportedFieldType("a".to_owned()))
+        );
+    }
+
+    #[test]
+    fn display_values_as_cmajor_literals() {
+        assert_eq!(Value::from(true).to_string(), "true");
+        assert_eq!(Value::from(42).to_string(), "42");
+        assert_eq!(Value::from(42_i64).to_string(), "42_i64");
+        assert_eq!(Value::from(3.5_f32).to_string(), "3.5f");
+        assert_eq!(Value::from(3.0_f64).to_string(), "3.0");
+        assert_eq!(
+            Value::from([[1, 2], [3, 4]]).to_string(),
+            "int32[2][] (int32[] (1, 2), int32[] (3, 4))"
+        );
+        assert_eq!(
+            Value::from(Complex32 {
+                real: 1.0,
+                imag: 2.0
+            })
+            .to_string(),
+            "complex32 (real: 1.0f, imag: 2.0f)"
+        );
+    }
+
+    #[test]
+    fn object_with_an_array_field_is_serialised() {
+        let value: Value = ObjectValue::builder("S")
+            .field("a", 5)
+            .field("b", ArrayValue::from_slice(&[1.0_f32, 2.0]))
+            .build()
+            .unwrap()
+            .into();
+
+        let mut expected = vec![8, 2, b'S', 0];
+        expected.extend_from_slice(&[1, b'a', 0]);
+        expected.extend_from_slice(&[7, 1, 2, 3, b'b', 0]);
+        expected.extend_from_slice(&5_i32.to_ne_bytes());
+        expected.extend_from_slice(&1.0_f32.to_ne_bytes());
+        expected.extend_from_slice(&2.0_f32.to_ne_bytes());
+
+        assert_eq!(value.serialise_as_choc_value(), expected);
+    }
+
+    #[test]
+    fn value_refs_can_be_compared_with_primitives() {
+        assert_eq!(ValueRef::Int32(5), 5);
+        assert_eq!(ValueRef::Float64(0.5), 0.5);
+        assert_eq!(ValueRef::Bool(true), true);
+        assert_ne!(ValueRef::Int64(5), 5_i32);
+        assert_ne!(ValueRef::Float32(f32::NAN), f32::NAN);
+    }
+
+    #[test]
+    fn scalar_arrays_can_be_borrowed_as_slices() {
+        let samples: Vec<f32> = (0..256).map(|i| i as f32).collect();
+        let array = ArrayValue::from_slice(&samples);
+
+        assert_eq!(array.as_ref().as_slice::<f32>(), Some(samples.as_slice()));
+        assert_eq!(array.as_ref().as_slice::<i32>(), None);
+
+        let nested = ArrayValue::from([[1_i32, 2], [3, 4]]);
+        assert_eq!(nested.as_ref().as_slice::<i32>(), None);
+    }
+
+    #[test]
+    fn arrays_are_aligned_for_their_element_type() {
+        for len in 0..8 {
+            let ints: Vec<i64> = (0..len).collect();
+            let array = ArrayValue::from_slice(&ints);
+            assert_eq!(array.as_ref().as_slice::<i64>(), Some(ints.as_slice()));
+
+            let floats: Vec<f64> = ints.iter().map(|&i| i as f64).collect();
+            let array = ArrayValue::from_slice(&floats);
+            assert_eq!(array.as_ref().as_slice::<f64>(), Some(floats.as_slice()));
+        }
+
+        let nested = ArrayValue::from([[1_i64, 2], [3, 4]]);
+        let nested = nested.as_ref();
+        let row = nested.get(1).unwrap();
+        assert_eq!(
+            row.as_array().unwrap().as_slice::<i64>(),
+            Some([3, 4].as_slice())
+        );
+    }
+
+    #[test]
+    fn arrays_in_packed_object_fields_may_be_unaligned() {
+        let object = ObjectValue::builder("S")
+            .field("a", 1_i32)
+            .field("b", [2_i64, 3])
+            .field("c", [4_i32, 5])
+            .build()
+            .unwrap();
+        let object = object.as_ref();
+
+        let b = object.field("b").unwrap();
+        assert_eq!(b.as_array().unwrap().as_slice::<i64>(), None);
+
+        let c = object.field("c").unwrap();
+        assert_eq!(
+            c.as_array().unwrap().as_slice::<i32>(),
+            Some([4, 5].as_slice())
+        );
+    }
+
+    #[test]
+    fn complex_arrays_round_trip() {
+        let spectrum = [
+            Complex32 {
+                real: 1.0,
+                imag: 2.0,
+            },
+            Complex32 {
+                real: -3.0,
+                imag: 0.5,
+            },
+        ];
+
+        let array = ArrayValue::from(spectrum);
+        assert_eq!(array.as_ref().len(), 2);
+        assert_eq!(<[Complex32; 2]>::try_from(array.as_ref()), Ok(spectrum));
+        assert_eq!(<[Complex32; 3]>::try_from(array.as_ref()), Err(()));
+        assert_eq!(ValueRef::Array(array.as_ref()).type_name(), "complex32[2]");
+        assert_eq!(<Vec<Complex64>>::try_from(array.as_ref()), Err(()));
+
+        let nested: Value = vec![[Complex64::default(); 2]; 3].into();
+        assert_eq!(
+            <Vec<[Complex64; 2]>>::try_from(nested.as_ref()),
+            Ok(vec![[Complex64::default(); 2]; 3])
+        );
+    }
+
+    #[test]
+    fn empty_arrays_are_serialised() {
+        let value: Value = ArrayValue::from_slice::<f32>(&[]).into();
+        assert_eq!(value.ty().size(), 0);
+        assert_eq!(value.serialise_as_choc_value(), [7, 0]);
+    }
+
+    #[test]
+    fn values_are_converted_to_json() {
+        let value: Value = ObjectValue::builder("S")
+            .field("a", true)
+            .field("b", [1, 2, 3])
+            .field(
+                "c",
+                Complex32 {
+                    real: 0.5,
+                    imag: f32::NAN,
+                },
+            )
+            .build()
+            .unwrap()
+            .into();
+
+        assert_eq!(
+            value.to_json(),
+            serde_json::json!({
+                "a": true,
+                "b": [1, 2, 3],
+                "c": { "real": 0.5, "imag": null },
+            })
+        );
+    }
+
+    #[test]
+    fn values_are_constructed_from_json() {
+        let ty: Type = Object::new("S")
+            .with_field("a", Type::Bool)
+            .with_field("b", Array::new(Type::Int64, 2))
+            .with_field("c", Type::Float64)
+            .into();
+
+        let value = Value::from_json(
+            &ty,
+            &serde_json::json!({ "a": true, "b": [1, 2.0], "c": 3 }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.to_json(),
+            serde_json::json!({ "a": true, "b": [1, 2], "c": 3.0 })
+        );
+    }
+
+    #[test]
+    fn constructing_values_from_json_reports_mismatches() {
+        let ty: Type = Object::new("S")
+            .with_field("a", Array::new(Type::Int32, 2))
+            .into();
+
+        assert_eq!(
+            Value::from_json(&ty, &serde_json::json!({ "a": [1, 2.5] })),
+            Err(FromJsonError::TypeMismatch {
+                path: "value.a[1]".to_owned(),
+                expected: "int32".to_owned()
+            })
+        );
+        assert_eq!(
+            Value::from_json(&ty, &serde_json::json!({ "a": [1, 1e10] })),
+            Err(FromJsonError::OutOfRange {
+                path: "value.a[1]".to_owned(),
+                expected: "int32".to_owned()
+            })
+        );
+        assert_eq!(
+            Value::from_json(&ty, &serde_json::json!({ "a": [1] })),
+            Err(FromJsonError::LengthMismatch {
+                path: "value.a".to_owned(),
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let empty = Type::from(Array::new(Type::Float32, 0));
+        assert_eq!(
+            Value::from_json(&empty, &serde_json::json!(1)),
+            Err(FromJsonError::TypeMismatch {
+                path: "value".to_owned(),
+                expected: empty.as_ref().type_name()
+            })
+        );
+        assert_eq!(empty.as_ref().type_name(), "float32[]");
+        assert_eq!(
+            Value::from_json(&ty, &serde_json::json!({})),
+            Err(FromJsonError::MissingField {
+                path: "value".to_owned(),
+                field: "a".to_owned()
+            })
+        );
+    }
+
     #[test]
     fn value_is_16_bytes() {
         assert_eq!(size_of::<Value>(), 16);
@@ -1,12 +1,18 @@
-use cmajor::{
-    engine::{Engine, Loaded},
-    json,
-    performer::{EndpointError, InputStream, InputValue, OutputEvent, OutputValue, Performer},
-    value::{
-        types::{Object, Type},
-        Complex32, Complex64, Value, ValueRef,
+use {
+    cmajor::{
+        engine::{AnnotationExt, Engine, Loaded},
+        json,
+        performer::{
+            Endpoint, EndpointError, InputStream, InputValue, OutputEvent, OutputStream,
+            OutputValue, Performer,
+        },
+        value::{
+            types::{Object, Type},
+            ArrayValue, CmajorValue, Complex32, Complex64, ObjectValue, Value, ValueRef,
+        },
+        Cmajor,
     },
-    Cmajor,
+    std::sync::{Arc, Mutex},
 };
 
 fn setup<E>(program: &str, endpoints: impl FnOnce(&mut Engine<Loaded>) -> E) -> (Performer, E) {
@@ -69,6 +75,206 @@ fn can_read_and_write_to_value_endpoint() {
     assert!(performer.get::<bool>(bool_out));
 }
 
+#[test]
+fn can_read_and_write_typed_arrays() {
+    const PROGRAM: &str = r#"
+        processor Reverse
+        {
+            input value int[4] in;
+            output value int[4] out;
+            input value bool[2] flags_in;
+            output value bool[2] flags_out;
+
+            void main()
+            {
+                loop {
+                    out <- int[4] (in[3], in[2], in[1], in[0]);
+                    flags_out <- bool[2] (flags_in[1], flags_in[0]);
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output, flags_in, flags_out)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputValue<[i32; 4]>>("in").unwrap(),
+            engine.endpoint::<OutputValue<[i32; 4]>>("out").unwrap(),
+            engine
+                .endpoint::<InputValue<[bool; 2]>>("flags_in")
+                .unwrap(),
+            engine
+                .endpoint::<OutputValue<[bool; 2]>>("flags_out")
+                .unwrap(),
+        )
+    });
+
+    performer.set(input, [1, 2, 3, 4]);
+    performer.set(flags_in, [true, false]);
+    performer.advance();
+
+    assert_eq!(performer.get(output), [4, 3, 2, 1]);
+    assert_eq!(performer.get(flags_out), [false, true]);
+}
+
+#[test]
+fn typed_arrays_must_match_the_endpoint_type() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input value int[4] in;
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let cmajor = Cmajor::new();
+    let program = cmajor.parse(PROGRAM).unwrap();
+    let mut engine = cmajor
+        .create_default_engine()
+        .build()
+        .load(&program)
+        .unwrap();
+
+    assert!(matches!(
+        engine.endpoint::<InputValue<[i32; 3]>>("in"),
+        Err(EndpointError::DataTypeMismatch)
+    ));
+    assert!(matches!(
+        engine.endpoint::<InputValue<[f32; 4]>>("in"),
+        Err(EndpointError::DataTypeMismatch)
+    ));
+}
+
+#[test]
+fn can_read_output_values_from_another_thread() {
+    const PROGRAM: &str = r#"
+        processor Counter
+        {
+            output value int count;
+            output value bool odd;
+
+            void main()
+            {
+                int n = 0;
+                loop {
+                    n++;
+                    count <- n;
+                    odd <- (n % 2) == 1;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, (count, odd)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<OutputValue<i32>>("count").unwrap(),
+            engine.endpoint::<OutputValue<bool>>("odd").unwrap(),
+        )
+    });
+
+    let count = performer.output_value_reader(count);
+    let odd = performer.output_value_reader(odd);
+
+    performer.advance();
+    performer.advance();
+    performer.advance();
+
+    let (count, odd) = std::thread::spawn(move || (count.get(), odd.get()))
+        .join()
+        .unwrap();
+
+    assert_eq!(count, 3);
+    assert!(odd);
+}
+
+#[test]
+fn can_update_inputs_from_a_split_handle() {
+    const PROGRAM: &str = r#"
+        processor Accumulator
+        {
+            input value int in;
+            input event int add;
+            output value int out;
+
+            int total;
+
+            event add(int value)
+            {
+                total += value;
+            }
+
+            void main()
+            {
+                loop {
+                    out <- in + total;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (performer, (input, add, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("add").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    let (mut performer, mut handle) = performer.split();
+
+    std::thread::spawn(move || {
+        handle.write_value(input, 10).unwrap();
+        handle.post_event(add, 5).unwrap();
+        handle.post_event(add, 2).unwrap();
+    })
+    .join()
+    .unwrap();
+
+    performer.advance();
+
+    assert_eq!(performer.get::<i32>(output), 17);
+}
+
+#[test]
+fn split_handles_report_a_full_queue() {
+    const PROGRAM: &str = r#"
+        processor Test
+        {
+            input event int in;
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (performer, input) = setup(PROGRAM, |engine| engine.endpoint("in").unwrap());
+
+    let (mut performer, mut handle) = performer.split_with_capacity(2);
+    assert!(handle.is_empty());
+    assert_eq!(handle.remaining_capacity(), 2);
+
+    handle.post_event(input, 1).unwrap();
+    handle.post_event(input, 2).unwrap();
+    assert_eq!(handle.len(), 2);
+    assert_eq!(handle.remaining_capacity(), 0);
+    assert!(matches!(
+        handle.post_event(input, 3),
+        Err(EndpointError::QueueFull)
+    ));
+
+    performer.advance();
+    assert!(handle.is_empty());
+    assert_eq!(handle.remaining_capacity(), 2);
+}
+
 #[test]
 fn cant_access_endpoints_with_wrong_type() {
     const PROGRAM: &str = r#"
@@ -143,6 +349,51 @@ fn can_read_and_write_complex32_numbers() {
     );
 }
 
+#[test]
+fn can_read_output_values_as_other_types() {
+    const PROGRAM: &str = r#"
+        processor Test
+        {
+            output value complex64[2] out;
+
+            void main()
+            {
+                out <- complex64[2] (complex64 (1.0, 2.0), complex64 (3.0, 4.0));
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (output, other_output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("out").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    performer.advance();
+
+    let result: [Complex64; 2] = performer.get_as(output).unwrap();
+    assert_eq!(
+        result,
+        [
+            Complex64 {
+                real: 1.0,
+                imag: 2.0
+            },
+            Complex64 {
+                real: 3.0,
+                imag: 4.0
+            }
+        ]
+    );
+
+    assert!(matches!(
+        performer.get_as::<[Complex32; 2]>(other_output),
+        Err(EndpointError::DataTypeMismatch)
+    ));
+}
+
 #[test]
 fn can_read_and_write_complex64_numbers() {
     const PROGRAM: &str = r#"
@@ -221,63 +472,198 @@ fn can_read_structs() {
 }
 
 #[test]
-fn can_read_and_write_arrays() {
+fn can_ramp_input_values() {
     const PROGRAM: &str = r#"
-        processor Reverser
+        processor Ramp
         {
-            input value int[4] in;
-            output value int[4] out;
+            input value float gain;
+            output stream float out;
 
             void main()
             {
-                out <- int[] (in[3], in[2], in[1], in[0]);
-                advance();
+                loop
+                {
+                    out <- gain;
+                    advance();
+                }
             }
         }
     "#;
 
-    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+    let (mut performer, (gain, output)) = setup(PROGRAM, |engine| {
         (
-            engine.endpoint("in").unwrap(),
-            engine.endpoint("out").unwrap(),
+            engine.endpoint::<InputValue<f32>>("gain").unwrap(),
+            engine.endpoint::<OutputStream<f32>>("out").unwrap(),
         )
     });
 
-    performer.set::<Value>(input, [1, 2, 3, 4].into()).unwrap();
+    performer.set_ramped(gain, 1.0, 64);
+    performer.advance();
+
+    let mut buffer = [0.0; 128];
+    performer.read(output, &mut buffer);
+
+    assert!(buffer[0] < 1.0);
+    assert!(buffer.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(buffer[127], 1.0);
+}
+
+#[test]
+fn buffered_values_are_applied_on_advance() {
+    const PROGRAM: &str = r#"
+        processor Buffered
+        {
+            input value int a;
+            input value int b;
+            output value int sum;
+
+            void main()
+            {
+                loop
+                {
+                    sum <- a + b;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, (a, b, sum)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputValue<i32>>("a").unwrap(),
+            engine.endpoint::<InputValue<i32>>("b").unwrap(),
+            engine.endpoint::<OutputValue<i32>>("sum").unwrap(),
+        )
+    });
 
+    performer.set_block_size(1);
+    performer.set_buffered(true);
+    assert!(performer.is_buffered());
+
+    performer.set(a, 1);
+    performer.set(b, 2);
+    performer.set(a, 10);
     performer.advance();
+    assert_eq!(performer.get(sum), 12);
 
-    let value = performer.get::<Value>(output).unwrap();
-    let array = value.as_array().unwrap();
+    performer.set(b, 5);
+    performer.set_buffered(false);
+    performer.advance();
+    assert_eq!(performer.get(sum), 15);
+}
 
-    assert_eq!(array.len(), 4);
+#[test]
+fn class_names_can_be_ignored_when_setting_values() {
+    #[derive(CmajorValue)]
+    #[cmajor(class = "my_crate::Params")]
+    struct Params {
+        gain: f32,
+    }
 
-    assert_eq!(array.get(0), Some(ValueRef::Int32(4)));
-    assert_eq!(array.get(1), Some(ValueRef::Int32(3)));
-    assert_eq!(array.get(2), Some(ValueRef::Int32(2)));
-    assert_eq!(array.get(3), Some(ValueRef::Int32(1)));
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input value Params in;
+            output value float out;
+
+            struct Params
+            {
+                float gain;
+            }
+
+            void main()
+            {
+                loop
+                {
+                    out <- in.gain;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputValue>("in").unwrap(),
+            engine.endpoint::<OutputValue<f32>>("out").unwrap(),
+        )
+    });
+
+    let result = performer.set(input.clone(), Params { gain: 0.5 }.into());
+    assert!(matches!(result, Err(EndpointError::DataTypeMismatch)));
+
+    performer.set_ignore_class_names(true);
+    performer
+        .set(input.clone(), Params { gain: 0.5 }.into())
+        .unwrap();
+
+    performer.set_block_size(1);
+    performer.advance();
+    assert_eq!(performer.get(output), 0.5);
+
+    let (mut performer, mut handle) = performer.split();
+    handle
+        .write_value(input, Params { gain: 0.25 }.into())
+        .unwrap();
+
+    performer.advance();
+    assert_eq!(performer.get(output), 0.25);
 }
 
 #[test]
-fn can_post_events() {
+fn output_values_can_be_copied_into_a_buffer() {
     const PROGRAM: &str = r#"
         processor P
         {
-            input event (int, bool) in;
-            output value int out;
+            output value float32[2] out;
 
-            event in (int x)
+            void main()
             {
-                out <- x * x;
+                out <- float32[2] (1.0f, 2.0f);
+                advance();
             }
+        }
+    "#;
 
-            event in (bool x)
+    let (mut performer, output) = setup(PROGRAM, |engine| {
+        engine.endpoint::<OutputValue>("out").unwrap()
+    });
+
+    performer.advance();
+
+    let mut buffer = Vec::new();
+    let ty = performer.copy_output_into(output, &mut buffer).clone();
+    assert_eq!(buffer.len(), 8);
+
+    let value = ValueRef::from_bytes(ty.as_ref(), &buffer).unwrap();
+    assert_eq!(<[f32; 2]>::try_from(value), Ok([1.0, 2.0]));
+}
+
+#[test]
+fn can_read_and_write_derived_structs() {
+    #[derive(Debug, PartialEq, CmajorValue)]
+    struct Params {
+        cutoff: f32,
+        resonance: f32,
+        bypass: bool,
+    }
+
+    const PROGRAM: &str = r#"
+        processor Echo
+        {
+            input value Params in;
+            output value Params out;
+
+            struct Params
             {
-                out <- x ? 42 : 0;
+                float cutoff;
+                float resonance;
+                bool bypass;
             }
 
             void main()
             {
+                out <- in;
                 advance();
             }
         }
@@ -290,81 +676,812 @@ fn can_post_events() {
         )
     });
 
-    performer.post(input, 4).unwrap();
-    performer.advance();
+    let value: Value = Params {
+        cutoff: 440.0,
+        resonance: 0.5,
+        bypass: true,
+    }
+    .into();
+    performer.set(input, value).unwrap();
+
+    performer.advance();
+
+    let result: Params = performer.get::<Value>(output).unwrap().try_into().unwrap();
+
+    assert_eq!(
+        result,
+        Params {
+            cutoff: 440.0,
+            resonance: 0.5,
+            bypass: true,
+        }
+    );
+}
+
+#[test]
+fn can_read_and_write_arrays() {
+    const PROGRAM: &str = r#"
+        processor Reverser
+        {
+            input value int[4] in;
+            output value int[4] out;
+
+            void main()
+            {
+                out <- int[] (in[3], in[2], in[1], in[0]);
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    performer.set::<Value>(input, [1, 2, 3, 4].into()).unwrap();
+
+    performer.advance();
+
+    let value = performer.get::<Value>(output).unwrap();
+    let array = value.as_array().unwrap();
+
+    assert_eq!(array.len(), 4);
+
+    assert_eq!(array.get(0), Some(ValueRef::Int32(4)));
+    assert_eq!(array.get(1), Some(ValueRef::Int32(3)));
+    assert_eq!(array.get(2), Some(ValueRef::Int32(2)));
+    assert_eq!(array.get(3), Some(ValueRef::Int32(1)));
+}
+
+#[test]
+fn can_write_arrays_from_runtime_length_slices() {
+    const PROGRAM: &str = r#"
+        processor Reverser
+        {
+            input value int[4] in;
+            output value int[4] out;
+
+            void main()
+            {
+                out <- int[] (in[3], in[2], in[1], in[0]);
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    let elems: Vec<i32> = (1..=4).collect();
+    performer
+        .set::<Value>(input, ArrayValue::from_slice(&elems).into())
+        .unwrap();
+
+    performer.advance();
+
+    let value = performer.get::<Value>(output).unwrap();
+    let array = value.as_array().unwrap();
+
+    assert_eq!(array.len(), 4);
+    assert_eq!(array.get(0), Some(ValueRef::Int32(4)));
+    assert_eq!(array.get(3), Some(ValueRef::Int32(1)));
+}
+
+#[test]
+fn cant_write_arrays_of_the_wrong_length() {
+    const PROGRAM: &str = r#"
+        processor Sink
+        {
+            input value int[4] in;
+            output value int out;
+
+            void main()
+            {
+                out <- in[0];
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (a, b)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("in").unwrap(),
+        )
+    });
+
+    assert!(matches!(
+        performer.set::<Value>(a, [1, 2, 3].into()),
+        Err(EndpointError::DataTypeMismatch)
+    ));
+    assert!(matches!(
+        performer.set::<Value>(b, [1, 2, 3, 4, 5].into()),
+        Err(EndpointError::DataTypeMismatch)
+    ));
+}
+
+#[test]
+fn can_count_audio_channels() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input stream float<2> in;
+            input stream float sidechain;
+            input value float gain;
+            output stream float<2> out;
+
+            void main()
+            {
+                loop
+                {
+                    out <- in * gain;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (performer, _) = setup(PROGRAM, |engine| {
+        let _: Endpoint<InputStream<[f32; 2]>> = engine.endpoint("in").unwrap();
+        let _: Endpoint<InputStream<f32>> = engine.endpoint("sidechain").unwrap();
+        let _: Endpoint<InputValue<f32>> = engine.endpoint("gain").unwrap();
+        let _: Endpoint<OutputStream<[f32; 2]>> = engine.endpoint("out").unwrap();
+    });
+
+    assert_eq!(performer.input_channels(), 3);
+    assert_eq!(performer.output_channels(), 2);
+}
+
+#[test]
+fn can_post_events() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input event (int, bool) in;
+            output value int out;
+
+            event in (int x)
+            {
+                out <- x * x;
+            }
+
+            event in (bool x)
+            {
+                out <- x ? 42 : 0;
+            }
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    performer.post(input, 4).unwrap();
+    performer.advance();
+
+    assert_eq!(performer.get::<i32>(output), 16);
+
+    performer.post(input, true).unwrap();
+    performer.advance();
+
+    assert_eq!(performer.get(output), 42);
+}
+
+#[test]
+fn can_post_structs_with_different_class_names() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input event (int, Point) in;
+            output value float out;
+
+            struct Point
+            {
+                float x;
+                float y;
+            }
+
+            event in (int x)
+            {
+                out <- float(x);
+            }
+
+            event in (Point p)
+            {
+                out <- p.x + p.y;
+            }
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    let point = ObjectValue::builder("my_crate::Point")
+        .field("x", 1.5_f32)
+        .field("y", 2.0_f32)
+        .build()
+        .unwrap();
+
+    performer.post(input, &point).unwrap();
+    performer.advance();
+
+    assert_eq!(performer.get::<f32>(output), 3.5);
+}
+
+#[test]
+fn can_fetch_events_from_every_endpoint() {
+    const PROGRAM: &str = r#"
+        processor Test
+        {
+            output event int a;
+            output event float b;
+
+            void main()
+            {
+                a <- 1;
+                b <- 2.0f;
+                a <- 3;
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (a, b)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<OutputEvent>("a").unwrap(),
+            engine.endpoint::<OutputEvent>("b").unwrap(),
+        )
+    });
+
+    performer.advance();
+
+    let (mut from_a, mut from_b) = (vec![], vec![]);
+    let count = performer.fetch_all(|endpoint, _, event| {
+        if endpoint.handle() == a.handle() {
+            from_a.push(event.to_owned());
+        } else if endpoint.handle() == b.handle() {
+            from_b.push(event.to_owned());
+        }
+    });
+
+    assert_eq!(count, 3);
+    assert_eq!(from_a, [Value::Int32(1), Value::Int32(3)]);
+    assert_eq!(from_b, [Value::Float32(2.0)]);
+}
+
+#[test]
+fn can_read_events() {
+    const PROGRAM: &str = r#"
+        processor Echo
+        {
+            input event (int, bool) in;
+            output event (int, bool) out;
+
+            event in(int value)
+            {
+                out <- value;
+            }
+
+            event in(bool value)
+            {
+                out <- value;
+            }
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    performer.post(input, 5).unwrap();
+    performer.advance();
+
+    let mut events = vec![];
+    performer
+        .fetch(output, |frame, event| {
+            events.push((frame, event.to_owned()));
+        })
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0], (0, Value::Int32(5)));
+
+    performer.post(input, true).unwrap();
+    performer.advance();
+
+    performer
+        .fetch(output, |frame, event| {
+            events.push((frame, event.to_owned()));
+        })
+        .unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1], (0, Value::Bool(true)));
+}
+
+#[test]
+fn events_can_be_collected() {
+    const PROGRAM: &str = r#"
+        processor Echo
+        {
+            input event (int, bool) in;
+            output event (int, bool) out;
+
+            event in(int value)
+            {
+                out <- value;
+            }
+
+            event in(bool value)
+            {
+                out <- value;
+            }
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    performer.post(input, 7).unwrap();
+    performer.advance();
+
+    assert_eq!(
+        performer.collect_events(output).unwrap(),
+        [(0, Value::Int32(7))]
+    );
+}
+
+#[test]
+fn can_read_typed_events() {
+    const PROGRAM: &str = r#"
+        processor Echo
+        {
+            input event (int, bool) in;
+            output event (int, bool) out;
+
+            event in(int value)
+            {
+                out <- value;
+            }
+
+            event in(bool value)
+            {
+                out <- value;
+            }
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    performer.post(input, 5).unwrap();
+    performer.post(input, true).unwrap();
+    performer.post(input, 7).unwrap();
+    performer.advance();
+
+    assert_eq!(performer.fetch(output, |_, _| {}).unwrap(), 3);
+
+    let mut events = vec![];
+    let count = performer
+        .fetch_typed::<i32>(output, |_, value| events.push(value))
+        .unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(events, [5, 7]);
+}
+
+#[test]
+fn can_post_batches_of_events() {
+    const PROGRAM: &str = r#"
+        processor Sum
+        {
+            input event int in;
+            output value int out;
+
+            int total;
+
+            event in(int value)
+            {
+                total += value;
+            }
+
+            void main()
+            {
+                loop {
+                    out <- total;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("in").unwrap(),
+            engine.endpoint("out").unwrap(),
+        )
+    });
+
+    performer.post_all(input, [1, 2, 3, 4]).unwrap();
+    assert!(matches!(
+        performer.post_all(input, [1.0_f32]),
+        Err(EndpointError::DataTypeMismatch)
+    ));
+
+    performer.advance();
+
+    assert_eq!(performer.get::<i32>(output), 10);
+}
+
+#[test]
+fn can_post_and_fetch_midi_messages() {
+    const PROGRAM: &str = r#"
+        processor Transpose
+        {
+            input event std::midi::Message midiIn;
+            output event std::midi::Message midiOut;
+
+            event midiIn(std::midi::Message message)
+            {
+                midiOut <- std::midi::createMessage (message.getStatusByte(),
+                                                     message.getNoteNumber() + 12,
+                                                     message.getVelocity());
+            }
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint("midiIn").unwrap(),
+            engine.endpoint("midiOut").unwrap(),
+        )
+    });
+
+    performer.post_midi(input, [0x90, 60, 100]).unwrap();
+    performer.advance();
+
+    let mut messages = vec![];
+    let count = performer
+        .fetch_midi(output, |_, message| messages.push(message))
+        .unwrap();
+
+    assert_eq!(count, 1);
+    assert_eq!(messages, [[0x90, 72, 100]]);
+}
+
+#[test]
+fn can_read_streams() {
+    const PROGRAM: &str = r#"
+        processor Iota
+        {
+            output stream int out;
+
+            void main()
+            {
+                int i = 0;
+                loop {
+                    out <- i;
+                    i += 1;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, stream) = setup(PROGRAM, |engine| engine.endpoint("out").unwrap());
+
+    performer.set_block_size(8);
+
+    performer.advance();
+
+    let mut buffer = [0_i32; 8];
+
+    performer.read(stream, buffer.as_mut_slice());
+    assert_eq!(buffer, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+    performer.advance();
+
+    performer.read(stream, buffer.as_mut_slice());
+    assert_eq!(buffer, [8, 9, 10, 11, 12, 13, 14, 15]);
+
+    assert_eq!(performer.get_xruns(), 0);
+}
+
+#[test]
+fn xruns_can_be_reset() {
+    const PROGRAM: &str = r#"
+        processor Iota
+        {
+            output stream int out;
+
+            void main()
+            {
+                int i = 0;
+                loop {
+                    out <- i;
+                    i += 1;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, _) = setup(PROGRAM, |engine| {
+        engine.endpoint::<OutputStream<i32>>("out").unwrap()
+    });
+
+    performer.set_block_size(8);
+    performer.advance();
+
+    performer.reset_xruns();
+    assert_eq!(performer.get_xruns(), 0);
+}
+
+#[test]
+fn advancing_without_xruns_is_reported() {
+    const PROGRAM: &str = r#"
+        processor Iota
+        {
+            output stream int out;
+
+            void main()
+            {
+                int i = 0;
+                loop {
+                    out <- i;
+                    i += 1;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, _) = setup(PROGRAM, |engine| {
+        engine.endpoint::<OutputStream<i32>>("out").unwrap()
+    });
+
+    performer.set_block_size(8);
+    assert!(!performer.advance_checked());
+    assert_eq!(performer.get_xruns(), 0);
+}
+
+#[test]
+fn can_read_whole_blocks_from_streams() {
+    const PROGRAM: &str = r#"
+        processor Iota
+        {
+            output stream int out;
+
+            void main()
+            {
+                int i = 0;
+                loop {
+                    out <- i;
+                    i += 1;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, stream) = setup(PROGRAM, |engine| engine.endpoint("out").unwrap());
+
+    performer.set_block_size(4);
+    assert_eq!(performer.block_size(), 4);
+
+    performer.advance();
+    assert_eq!(performer.read_block(stream), [0, 1, 2, 3]);
+
+    let mut buffer = vec![0; 16];
+    performer.advance();
+    performer.read_block_into(stream, &mut buffer);
+    assert_eq!(buffer, [4, 5, 6, 7]);
+
+    assert_eq!(performer.advance_frames(2), 2);
+    assert_eq!(performer.block_size(), 2);
+    assert_eq!(performer.read_block(stream), [8, 9]);
+
+    let max_block_size = performer.get_max_block_size();
+    assert_eq!(performer.advance_frames(max_block_size + 1), max_block_size);
+    assert_eq!(performer.block_size(), max_block_size);
+}
+
+#[test]
+fn checked_stream_access_validates_the_block_size() {
+    const PROGRAM: &str = r#"
+        processor Gain
+        {
+            input stream float in;
+            output stream float out;
+
+            void main()
+            {
+                loop {
+                    out <- in * 2.0f;
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputStream<f32>>("in").unwrap(),
+            engine.endpoint::<OutputStream<f32>>("out").unwrap(),
+        )
+    });
+
+    performer.set_block_size(4);
 
-    assert_eq!(performer.get::<i32>(output), 16);
+    assert!(matches!(
+        performer.try_write(input, &[1.0; 3]),
+        Err(EndpointError::BufferLengthMismatch {
+            expected: 4,
+            found: 3
+        })
+    ));
 
-    performer.post(input, true).unwrap();
+    performer.try_write(input, &[1.0, 2.0, 3.0, 4.0]).unwrap();
     performer.advance();
 
-    assert_eq!(performer.get(output), 42);
+    let mut buffer = [0.0; 4];
+    performer.try_read(output, &mut buffer).unwrap();
+    assert_eq!(buffer, [2.0, 4.0, 6.0, 8.0]);
+
+    let mut buffer = [0.0; 8];
+    assert!(performer.try_read(output, &mut buffer).is_err());
 }
 
 #[test]
-fn can_read_events() {
+fn can_render_offline_in_blocks() {
     const PROGRAM: &str = r#"
-        processor Echo
+        processor Gain
         {
-            input event (int, bool) in;
-            output event (int, bool) out;
-
-            event in(int value)
-            {
-                out <- value;
-            }
-
-            event in(bool value)
-            {
-                out <- value;
-            }
+            input stream float in;
+            output stream float out;
 
             void main()
             {
-                advance();
+                loop {
+                    out <- in * 0.5f;
+                    advance();
+                }
             }
         }
     "#;
 
     let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
         (
-            engine.endpoint("in").unwrap(),
-            engine.endpoint("out").unwrap(),
+            engine.endpoint::<InputStream<f32>>("in").unwrap(),
+            engine.endpoint::<OutputStream<f32>>("out").unwrap(),
         )
     });
 
-    performer.post(input, 5).unwrap();
-    performer.advance();
+    let num_frames = performer.get_max_block_size() as usize * 2 + 3;
+    let input_buffer: Vec<f32> = (0..num_frames).map(|i| i as f32).collect();
+    let mut output_buffer = vec![0.0; num_frames];
 
-    let mut events = vec![];
     performer
-        .fetch(output, |frame, event| {
-            events.push((frame, event.to_owned()));
-        })
+        .render(
+            &[(input, &input_buffer)],
+            &mut [(output, &mut output_buffer)],
+            num_frames,
+        )
         .unwrap();
 
-    assert_eq!(events.len(), 1);
-    assert_eq!(events[0], (0, Value::Int32(5)));
-
-    performer.post(input, true).unwrap();
-    performer.advance();
+    assert!(output_buffer
+        .iter()
+        .enumerate()
+        .all(|(i, sample)| *sample == i as f32 * 0.5));
 
-    performer
-        .fetch(output, |frame, event| {
-            events.push((frame, event.to_owned()));
+    assert!(matches!(
+        performer.render(&[(input, &input_buffer[..1])], &mut [], 2),
+        Err(EndpointError::BufferLengthMismatch {
+            expected: 2,
+            found: 1
         })
-        .unwrap();
+    ));
+}
 
-    assert_eq!(events.len(), 2);
-    assert_eq!(events[1], (0, Value::Bool(true)));
+#[test]
+#[cfg(feature = "audio-file")]
+fn can_render_stereo_output_to_a_wav_file() {
+    use cmajor::performer::wav::render_to_wav;
+
+    const PROGRAM: &str = r#"
+        processor Stereo
+        {
+            output stream float<2> out;
+
+            void main()
+            {
+                loop {
+                    out <- float<2> (0.25f, -0.25f);
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, output) = setup(PROGRAM, |engine| {
+        engine.endpoint::<OutputStream<[f32; 2]>>("out").unwrap()
+    });
+
+    let path = std::env::temp_dir().join(format!("cmajor-render-{}.wav", std::process::id()));
+    let num_frames = performer.get_max_block_size() as usize + 10;
+    render_to_wav(&mut performer, output, num_frames, &path).unwrap();
+
+    let reader = hound::WavReader::open(&path).unwrap();
+    assert_eq!(reader.spec().channels, 2);
+    assert_eq!(reader.spec().sample_rate, 44_100);
+
+    let samples: Vec<f32> = reader.into_samples().map(Result::unwrap).collect();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(samples.len(), num_frames * 2);
+    assert_eq!(samples[..2], [0.25, -0.25]);
 }
 
 #[test]
-fn can_read_streams() {
+fn can_reset_the_performer() {
     const PROGRAM: &str = r#"
-        processor Iota
+        processor Counter
         {
-            output stream int out;
+            output value int out;
 
             void main()
             {
@@ -378,23 +1495,19 @@ fn can_read_streams() {
         }
     "#;
 
-    let (mut performer, stream) = setup(PROGRAM, |engine| engine.endpoint("out").unwrap());
+    let (mut performer, output) = setup(PROGRAM, |engine| engine.endpoint("out").unwrap());
 
-    performer.set_block_size(8);
+    performer.set_block_size(1);
 
     performer.advance();
-
-    let mut buffer = [0_i32; 8];
-
-    performer.read(stream, buffer.as_mut_slice());
-    assert_eq!(buffer, [0, 1, 2, 3, 4, 5, 6, 7]);
-
     performer.advance();
+    performer.advance();
+    assert_eq!(performer.get::<i32>(output), 2);
 
-    performer.read(stream, buffer.as_mut_slice());
-    assert_eq!(buffer, [8, 9, 10, 11, 12, 13, 14, 15]);
+    performer.reset();
 
-    assert_eq!(performer.get_xruns(), 0);
+    performer.advance();
+    assert_eq!(performer.get::<i32>(output), 0);
 }
 
 #[test]
@@ -448,6 +1561,34 @@ fn can_query_endpoint_information() {
     );
 }
 
+#[test]
+fn endpoint_information_can_be_found_by_handle_or_id() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input value float b;
+            output event int c;
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (performer, b) = setup(PROGRAM, |engine| {
+        engine.endpoint::<InputValue<f32>>("b").unwrap()
+    });
+
+    assert!(performer
+        .info_for_handle(b.handle())
+        .is_some_and(|info| info.id() == "b"));
+    assert!(performer
+        .endpoint_by_id("c")
+        .is_some_and(|info| info.is_output()));
+    assert!(performer.endpoint_by_id("missing").is_none());
+}
+
 #[test]
 fn can_write_streams() {
     const PROGRAM: &str = r#"
@@ -586,6 +1727,97 @@ fn endpoints_with_annotations() {
     );
 }
 
+#[test]
+fn annotations_have_typed_accessors() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input value float a [[ name: "foo", min: 0.5, max: 10.0, hidden: true ]];
+            output value int b [[ name: "bar", min: 1, max: 5, hidden: false ]];
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (performer, (a, b)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputValue<f32>>("a").unwrap(),
+            engine.endpoint::<OutputValue<i32>>("b").unwrap(),
+        )
+    });
+
+    let a = performer.endpoint_info(a).unwrap();
+    assert_eq!(a.annotation().get_str("name"), Some("foo"));
+    assert_eq!(a.annotation().get_range(), Some((0.5, 10.0)));
+    assert_eq!(a.annotation().get_bool("hidden"), Some(true));
+
+    let b = performer.endpoint_info(b).unwrap();
+    assert_eq!(b.annotation().get_i64("max"), Some(5));
+    assert_eq!(b.annotation().get_range(), Some((1.0, 5.0)));
+    assert_eq!(b.annotation().get_f64("missing"), None);
+}
+
+#[test]
+fn initial_values_fall_back_to_min() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input value float a [[ min: 0.5, max: 10.0 ]];
+            output value int b [[ min: 1, max: 5 ]];
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (performer, (a, b)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputValue<f32>>("a").unwrap(),
+            engine.endpoint::<OutputValue<i32>>("b").unwrap(),
+        )
+    });
+
+    let a = performer.endpoint_info(a).unwrap();
+    assert_eq!(a.initial_value(), Some(Value::Float32(0.5)));
+
+    let b = performer.endpoint_info(b).unwrap();
+    assert_eq!(b.initial_value(), Some(Value::Int32(1)));
+}
+
+#[test]
+fn initial_values_prefer_init_over_min() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            input value float gain [[ min: 0.0, max: 2.0, init: 1.0 ]];
+            input value bool bypass;
+
+            void main()
+            {
+                advance();
+            }
+        }
+    "#;
+
+    let (performer, (gain, bypass)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputValue<f32>>("gain").unwrap(),
+            engine.endpoint::<InputValue<bool>>("bypass").unwrap(),
+        )
+    });
+
+    let gain = performer.endpoint_info(gain).unwrap();
+    assert_eq!(gain.initial_value(), Some(Value::Float32(1.0)));
+
+    let bypass = performer.endpoint_info(bypass).unwrap();
+    assert_eq!(bypass.initial_value(), None);
+}
+
 #[test]
 fn multiple_handles_to_the_same_input_value_endpoint() {
     const PROGRAM: &str = r#"
@@ -733,6 +1965,57 @@ fn vector_stream_endpoints() {
     assert_eq!(output_buffer, [[2., 1.], [2., 1.], [2., 1.], [2., 1.]]);
 }
 
+#[test]
+fn planar_vector_stream_endpoints() {
+    const PROGRAM: &str = r#"
+        processor Swap
+        {
+            input stream float<2> in;
+            output stream float<2> out;
+
+            void main()
+            {
+                loop {
+                    out <- float<2> (in[1], in[0]);
+                    advance();
+                }
+            }
+        }
+    "#;
+
+    let (mut performer, (input, output)) = setup(PROGRAM, |engine| {
+        (
+            engine.endpoint::<InputStream<[f32; 2]>>("in").unwrap(),
+            engine.endpoint::<OutputStream<[f32; 2]>>("out").unwrap(),
+        )
+    });
+
+    let left = [1_f32, 2., 3., 4.];
+    let right = [5_f32, 6., 7., 8.];
+    let mut out_left = [0_f32; 4];
+    let mut out_right = [0_f32; 4];
+
+    performer.set_block_size(4);
+
+    assert!(matches!(
+        performer.write_planar(input, &[&left]),
+        Err(EndpointError::ChannelCountMismatch)
+    ));
+    assert!(matches!(
+        performer.write_planar(input, &[&left, &right[..3]]),
+        Err(EndpointError::ChannelLengthMismatch)
+    ));
+
+    performer.write_planar(input, &[&left, &right]).unwrap();
+    performer.advance();
+    performer
+        .read_planar(output, &mut [&mut out_left, &mut out_right])
+        .unwrap();
+
+    assert_eq!(out_left, right);
+    assert_eq!(out_right, left);
+}
+
 #[test]
 fn writing_to_console() {
     const PROGRAM: &str = r#"
@@ -752,6 +2035,40 @@ fn writing_to_console() {
     performer.advance();
 }
 
+#[test]
+fn capturing_console_output() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            output value int out;
+
+            void main() {
+                console <- "Hello, world!" <- 5 <- "🦀";
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, _) = setup(PROGRAM, |_| {});
+
+    let messages = Arc::new(Mutex::new(vec![]));
+    performer.set_console_handler({
+        let messages = Arc::clone(&messages);
+        move |value| messages.lock().unwrap().push(value.to_owned())
+    });
+
+    performer.advance();
+
+    assert_eq!(
+        *messages.lock().unwrap(),
+        vec![
+            Value::from("Hello, world!"),
+            Value::from(5),
+            Value::from("🦀")
+        ]
+    );
+}
+
 #[test]
 fn string_endpoints() {
     const PROGRAM: &str = r#"
@@ -778,3 +2095,40 @@ fn string_endpoints() {
 
     assert_eq!(performer.get_string(value), Some("Cool 🫘!"));
 }
+
+#[test]
+fn console_output_can_be_flushed_without_advancing() {
+    const PROGRAM: &str = r#"
+        processor P
+        {
+            output value int out;
+
+            void main() {
+                console <- "done";
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, _) = setup(PROGRAM, |_| {});
+    let delivered = Arc::new(Mutex::new(vec![]));
+    performer.set_console_handler({
+        let delivered = Arc::clone(&delivered);
+        move |value| delivered.lock().unwrap().push(value.to_owned())
+    });
+    performer.advance();
+    performer.flush_console();
+    assert_eq!(*delivered.lock().unwrap(), vec![Value::from("done")]);
+
+    let messages = Arc::new(Mutex::new(vec![]));
+    performer.set_console_handler({
+        let messages = Arc::clone(&messages);
+        move |value| messages.lock().unwrap().push(value.to_owned())
+    });
+
+    performer.flush_console();
+    assert_eq!(*messages.lock().unwrap(), vec![Value::from("done")]);
+
+    performer.flush_console();
+    assert_eq!(*messages.lock().unwrap(), vec![Value::from("done")]);
+}
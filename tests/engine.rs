@@ -1,9 +1,15 @@
-use cmajor::{
-    endpoint::EndpointDirection,
-    engine::{Engine, Error, Externals, Loaded},
-    performer::{OutputValue, Performer},
-    value::{types::Type, Complex32, ValueRef},
-    Cmajor,
+use {
+    cmajor::{
+        endpoint::{EndpointDirection, EndpointHandle},
+        engine::{Engine, Error, Externals, Loaded},
+        performer::{InputValue, OutputValue, Performer},
+        value::{types::Type, ArrayValue, Complex32, ObjectValue, ValueRef},
+        Cmajor,
+    },
+    std::sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc, Mutex,
+    },
 };
 
 #[test]
@@ -45,6 +51,229 @@ fn program_details() {
     assert!(matches!(input_endpoint.ty(), Type::Int32));
 }
 
+#[test]
+fn choosing_the_main_processor() {
+    let source_code = r#"
+        processor A {
+            output value int out;
+            void main() { out <- 1; advance(); }
+        }
+
+        processor B {
+            output value int out;
+            void main() { out <- 2; advance(); }
+        }
+    "#;
+
+    let cmajor = Cmajor::new();
+    let program = cmajor.parse(source_code).unwrap();
+    let engine = cmajor
+        .create_default_engine()
+        .with_sample_rate(48_000.0)
+        .with_main_processor("A")
+        .build();
+
+    let engine = engine.load(&program).unwrap();
+
+    assert_eq!(engine.program_details().main_processor(), "A");
+}
+
+#[test]
+fn engine_build_settings() {
+    let source_code = r#"
+        processor Test {
+            output value int out;
+            void main() { out <- processor.session; advance(); }
+        }
+    "#;
+
+    let cmajor = Cmajor::new();
+    let program = cmajor.parse(source_code).unwrap();
+    let engine = cmajor
+        .create_default_engine()
+        .with_sample_rate(48_000.0)
+        .with_max_frequency(96_000.0)
+        .with_max_block_size(64)
+        .with_max_state_size(1024 * 1024)
+        .with_session_id(1234)
+        .build();
+
+    let mut engine = engine.load(&program).unwrap();
+    let out = engine.endpoint::<OutputValue<i32>>("out").unwrap();
+    let engine = engine.link().unwrap();
+    assert_eq!(engine.sample_rate(), Some(48_000.0));
+    assert_eq!(engine.frames_to_seconds(24_000), Some(0.5));
+    assert_eq!(engine.seconds_to_frames(0.25), Some(12_000));
+
+    let mut performer = engine.performer();
+
+    assert_eq!(performer.get_max_block_size(), 64);
+    assert!(performer.event_buffer_size() > 0);
+    assert_eq!(performer.sample_rate(), Some(48_000.0));
+    assert_eq!(performer.latency_seconds(), Some(0.0));
+
+    performer.set_block_size(1);
+    performer.advance();
+    assert_eq!(performer.get(out), 1234);
+}
+
+#[test]
+fn engines_can_reload_programs() {
+    let cmajor = Cmajor::new();
+    let first = cmajor
+        .parse("processor A { output value int out; void main() { out <- 1; advance(); } }")
+        .unwrap();
+    let second = cmajor
+        .parse("processor B { output value int out; void main() { out <- 2; advance(); } }")
+        .unwrap();
+
+    let engine = cmajor
+        .create_default_engine()
+        .with_sample_rate(48_000.0)
+        .build();
+
+    let engine = engine.load(&first).unwrap().link().unwrap();
+
+    let mut engine = engine.reload(&second).unwrap();
+    let out = engine.endpoint::<OutputValue<i32>>("out").unwrap();
+    let mut performer = engine.link().unwrap().performer();
+
+    performer.set_block_size(1);
+    performer.advance();
+    assert_eq!(performer.get(out), 2);
+}
+
+#[test]
+fn engines_can_link_with_a_cache() {
+    let cmajor = Cmajor::new();
+    let program = cmajor
+        .parse("processor P { output value int out; void main() { out <- 3; advance(); } }")
+        .unwrap();
+
+    let cache = std::env::temp_dir().join(format!(
+        "cmajor-engines-can-link-with-a-cache-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&cache);
+
+    let cache_entries = || {
+        let mut entries = std::fs::read_dir(&cache)
+            .map(|entries| {
+                entries
+                    .map(|entry| {
+                        let entry = entry.unwrap();
+                        let modified = entry.metadata().unwrap().modified().unwrap();
+                        (entry.file_name(), modified)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        entries.sort();
+        entries
+    };
+
+    let link = || {
+        let engine = cmajor
+            .create_default_engine()
+            .with_sample_rate(48_000.0)
+            .build();
+
+        let mut engine = engine.load(&program).unwrap();
+        let out = engine.endpoint::<OutputValue<i32>>("out").unwrap();
+        let mut performer = engine.link_with_cache(&cache).unwrap().performer();
+
+        performer.set_block_size(1);
+        performer.advance();
+        assert_eq!(performer.get(out), 3);
+    };
+
+    link();
+    let stored = cache_entries();
+    assert!(!stored.is_empty(), "linking should store the program");
+
+    link();
+    assert_eq!(
+        cache_entries(),
+        stored,
+        "linking again should reload the program rather than storing it again"
+    );
+
+    std::fs::remove_dir_all(&cache).unwrap();
+}
+
+#[test]
+fn engine_types_can_be_queried() {
+    let cmajor = Cmajor::new();
+
+    let names: Vec<_> = cmajor
+        .engine_types()
+        .map(|engine_type| engine_type.name().to_owned())
+        .collect();
+    assert!(!names.is_empty());
+    assert!(names.iter().all(|name| cmajor.has_engine_type(name)));
+
+    assert!(!cmajor.has_engine_type("not-an-engine"));
+
+    for engine_type in cmajor.engine_types() {
+        assert!(cmajor.try_create_engine(engine_type).is_ok());
+    }
+
+    assert!(cmajor.try_create_default_engine().is_ok());
+}
+
+#[test]
+fn enumerate_endpoint_handles() {
+    let source_code = r#"
+        processor Test {
+            input value int in;
+            output stream float out;
+
+            void main() {
+                advance();
+            }
+        }
+    "#;
+
+    let cmajor = Cmajor::new();
+    let program = cmajor.parse(source_code).unwrap();
+    let mut engine = cmajor
+        .create_default_engine()
+        .with_sample_rate(48_000.0)
+        .build()
+        .load(&program)
+        .unwrap();
+
+    let endpoints = engine.endpoints().collect::<Vec<_>>();
+    assert_eq!(endpoints.len(), 2);
+
+    let ids = endpoints
+        .iter()
+        .map(|(info, _)| info.id().as_ref())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["in", "out"]);
+
+    let (_, handle) = endpoints[0];
+    assert_ne!(handle, endpoints[1].1);
+
+    let saved = u32::from(handle);
+    let input = engine
+        .endpoint_for_handle::<InputValue<i32>>(EndpointHandle::from(saved))
+        .unwrap();
+    assert_eq!(input.handle(), handle);
+
+    assert!(engine
+        .endpoint_for_handle::<InputValue<f32>>(handle)
+        .is_err());
+
+    assert_eq!(engine.endpoint_ids().collect::<Vec<_>>(), vec!["in", "out"]);
+
+    let performer = engine.link().unwrap().performer();
+    let mut ids = performer.endpoint_ids().collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, vec!["in", "out"]);
+}
+
+#[allow(clippy::result_large_err)]
 fn setup<E>(
     source_code: impl AsRef<str>,
     externals: Externals,
@@ -271,6 +500,114 @@ fn loading_external_variables_array() {
     assert_eq!(array.get(3), Some(ValueRef::Int32(4)));
 }
 
+#[test]
+fn loading_external_variables_runtime_length_array() {
+    let source_code = r#"
+        processor Test
+        {
+            output value int32 size;
+            output value float32 last;
+            external float32[] in;
+
+            void main()
+            {
+                size <- in.size;
+                last <- in[in.size - 1];
+                advance();
+            }
+        }
+    "#;
+
+    let samples: Vec<f32> = (0..100).map(|i| i as f32).collect();
+
+    let (mut performer, (size, last)) = setup(
+        source_code,
+        Externals::default().with_variable("Test::in", ArrayValue::from_slice(&samples)),
+        |engine| {
+            (
+                engine.endpoint("size").unwrap(),
+                engine.endpoint("last").unwrap(),
+            )
+        },
+    )
+    .unwrap();
+
+    performer.advance();
+    assert_eq!(performer.get::<i32>(size), 100);
+    assert_eq!(performer.get::<f32>(last), 99.0);
+}
+
+#[test]
+fn loading_external_variables_struct_with_array_field() {
+    let source_code = r#"
+        processor Test
+        {
+            struct S
+            {
+                int32 a;
+                int32[3] b;
+            }
+
+            output value int32 out;
+            external S in;
+
+            void main()
+            {
+                out <- in.a + in.b[0] + in.b[1] + in.b[2];
+                advance();
+            }
+        }
+    "#;
+
+    let value = ObjectValue::builder("S")
+        .field("a", 1)
+        .field("b", ArrayValue::from_slice(&[10, 100, 1000]))
+        .build()
+        .unwrap();
+
+    let (mut performer, out) = setup(
+        source_code,
+        Externals::default().with_variable("Test::in", value),
+        |engine| engine.endpoint("out").unwrap(),
+    )
+    .unwrap();
+
+    performer.advance();
+    assert_eq!(performer.get::<i32>(out), 1111);
+}
+
+#[test]
+fn loading_external_variables_string() {
+    let source_code = r#"
+        processor Test
+        {
+            output value string out;
+            external string in;
+
+            void main()
+            {
+                out <- in;
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, out) = setup(
+        source_code,
+        Externals::default().with_variable("Test::in", "Hello, world!"),
+        |engine| engine.endpoint::<OutputValue>("out").unwrap(),
+    )
+    .unwrap();
+
+    performer.advance();
+
+    let handle = match performer.get(out).unwrap() {
+        ValueRef::String(handle) => handle,
+        _ => panic!("expected string"),
+    };
+    assert_eq!(performer.get_string(handle), Some("Hello, world!"));
+}
+
 #[test]
 fn loading_external_variables_are_type_checked() {
     let source_code = r#"
@@ -302,6 +639,91 @@ fn loading_external_variables_are_type_checked() {
     assert!(result.is_err());
 }
 
+#[test]
+fn loading_user_defined_external_functions() {
+    static RECORDED: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn record(a: i32, b: i32) {
+        RECORDED.store(a * b, Ordering::SeqCst);
+    }
+
+    let source_code = r#"
+        namespace app
+        {
+            external void record (int32 a, int32 b);
+        }
+
+        processor Test
+        {
+            output stream float32 out;
+
+            void main()
+            {
+                app::record (6, 7);
+                advance();
+            }
+        }
+    "#;
+
+    let (mut performer, _) = setup(
+        source_code,
+        Externals::default().with_function("app::record", record as extern "C" fn(i32, i32)),
+        |_| {},
+    )
+    .unwrap();
+
+    performer.advance();
+    assert_eq!(RECORDED.load(Ordering::SeqCst), 42);
+}
+
+#[test]
+fn loading_external_functions_that_return_values() {
+    extern "C" fn clamp(value: i32, min: i32, max: i32) -> i32 {
+        value.clamp(min, max)
+    }
+
+    extern "C" fn half(value: f32) -> f32 {
+        value / 2.0
+    }
+
+    let source_code = r#"
+        namespace app
+        {
+            external int32 clamp (int32 value, int32 min, int32 max);
+            external float32 half (float32 value);
+        }
+
+        processor Test
+        {
+            output value int32 clamped;
+            output value float32 halved;
+
+            void main()
+            {
+                clamped <- app::clamp (12, 0, 10);
+                halved <- app::half (3.0f);
+                advance();
+            }
+        }
+    "#;
+
+    let externals = Externals::default()
+        .with_function("app::clamp", clamp as extern "C" fn(i32, i32, i32) -> i32)
+        .with_function("app::half", half as extern "C" fn(f32) -> f32);
+
+    let (mut performer, (clamped, halved)) = setup(source_code, externals, |engine| {
+        (
+            engine.endpoint::<OutputValue<i32>>("clamped").unwrap(),
+            engine.endpoint::<OutputValue<f32>>("halved").unwrap(),
+        )
+    })
+    .unwrap();
+
+    performer.advance();
+    assert_eq!(performer.get(clamped), 10);
+    assert_eq!(performer.get(halved), 1.5);
+}
+
 #[test]
 #[should_panic(
     expected = "assertion `left == right` failed: cmajor assertion failed\n  left: 4\n right: 5"
@@ -352,3 +774,38 @@ fn loading_external_functions() {
     let (mut performer, _) = setup(source_code, Externals::default(), |_| {}).unwrap();
     performer.advance();
 }
+
+#[test]
+fn debug_prints_can_be_redirected_to_a_sink() {
+    let source_code = r#"
+        namespace rust::debug
+        {
+            external void print (int32 value);
+            external void print (bool value);
+        }
+
+        processor Test
+        {
+            output stream float32 out;
+
+            void main()
+            {
+                rust::debug::print (42);
+                rust::debug::print (false);
+
+                advance();
+            }
+        }
+    "#;
+
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let externals = Externals::default().with_debug_sink({
+        let messages = Arc::clone(&messages);
+        move |message| messages.lock().unwrap().push(message.to_owned())
+    });
+
+    let (mut performer, _) = setup(source_code, externals, |_| {}).unwrap();
+    performer.advance();
+
+    assert_eq!(*messages.lock().unwrap(), ["42", "false"]);
+}
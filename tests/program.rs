@@ -18,7 +18,7 @@ fn compile_error() {
     let cmajor = Cmajor::new();
 
     let error = match cmajor.parse(program).unwrap_err() {
-        cmajor::ParseError::ParserError(parser_error) => parser_error,
+        cmajor::ParseError::ParserError(diagnostics) => diagnostics.into_iter().next().unwrap(),
         _ => panic!("expected parser error"),
     };
 
@@ -43,3 +43,99 @@ fn compile_error() {
         "3:19: error: Expected a stream type specifier"
     );
 }
+
+#[test]
+fn parse_errors_are_returned_as_diagnostics() {
+    let cmajor = Cmajor::new();
+
+    let diagnostics = match cmajor.parse("processor Test { input stweam int in; }") {
+        Err(cmajor::ParseError::ParserError(diagnostics)) => diagnostics,
+        _ => panic!("expected parser error"),
+    };
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics.has_errors());
+}
+
+#[test]
+fn compile_error_in_a_file() {
+    let path = std::env::temp_dir().join(format!(
+        "cmajor_compile_error_in_a_file-{}.cmajor",
+        std::process::id()
+    ));
+    std::fs::write(&path, "processor Test { input stweam int in; }").unwrap();
+
+    let cmajor = Cmajor::new();
+
+    let result = cmajor.parse_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let diagnostics = match result.unwrap_err() {
+        cmajor::ParseError::ParserError(diagnostics) => diagnostics,
+        _ => panic!("expected parser error"),
+    };
+
+    let error = diagnostics.iter().next().unwrap();
+    assert_eq!(error.file_name(), Some(path.to_string_lossy().as_ref()));
+}
+
+#[test]
+fn programs_without_diagnostics_have_no_warnings() {
+    let cmajor = Cmajor::new();
+
+    let program = cmajor
+        .parse(
+            r#"
+            processor Test {
+                output value int out;
+                void main() { out <- 42; advance(); }
+            }
+        "#,
+        )
+        .unwrap();
+
+    assert!(program.warnings().is_empty());
+}
+
+#[test]
+fn programs_can_have_multiple_sources() {
+    let cmajor = Cmajor::new();
+
+    let mut program = cmajor
+        .parse(
+            r#"
+            processor Test {
+                output value int out;
+                void main() { out <- Constants::answer; advance(); }
+            }
+        "#,
+        )
+        .unwrap();
+
+    program
+        .add_source(
+            "constants.cmajor",
+            "namespace Constants { let answer = 42; }",
+        )
+        .unwrap();
+
+    let engine = cmajor
+        .create_default_engine()
+        .with_sample_rate(44_100.0)
+        .build();
+    assert!(engine.load(&program).is_ok());
+}
+
+#[test]
+fn programs_can_be_included_from_files() {
+    const PROGRAM: &str = cmajor::include_cmajor!("programs/gain.cmajor");
+
+    let cmajor = Cmajor::new();
+    let program = cmajor.parse(PROGRAM).unwrap();
+
+    let engine = cmajor
+        .create_default_engine()
+        .with_sample_rate(44_100.0)
+        .build();
+    assert!(engine.load(&program).is_ok());
+}